@@ -10,10 +10,10 @@ pub fn massive_random(c: &mut Criterion) {
     let json_payload = read_to_string(&path).unwrap();
 
     c.bench_function("load", |b| {
-        b.iter(|| JSONValue::parse_and_verify(&json_payload))
+        b.iter(|| JSONValue::load_and_verify(&json_payload))
     });
 
-    let value = JSONValue::parse(&json_payload);
+    let value = JSONValue::load_and_verify(&json_payload);
     c.bench_function("single_retrieve", |b| {
         b.iter(|| {
             assert_eq!(
@@ -40,10 +40,10 @@ pub fn large_array(c: &mut Criterion) {
     let json_payload = read_to_string(&path).unwrap();
 
     c.bench_function("load_array", |b| {
-        b.iter(|| JSONValue::parse_and_verify(&json_payload))
+        b.iter(|| JSONValue::load_and_verify(&json_payload))
     });
 
-    let json = JSONValue::parse(&json_payload).unwrap();
+    let json = JSONValue::load_and_verify(&json_payload).unwrap();
     c.bench_function("read_array_sequentially", |b| {
         b.iter(|| {
             for (i, n) in json.iter_array().unwrap().enumerate() {
@@ -0,0 +1,482 @@
+use crate::{decode_key, JSONParsingError, JSONValue, JSONValueType};
+use core::cmp::Ordering;
+use core::hash::Hasher;
+
+impl<'a> JSONValue<'a> {
+    /// Whether this value represents the same data as `other`
+    ///
+    /// Unlike comparing the underlying text, this ignores whitespace, compares numbers by value
+    /// (so `1.0` equals `1`), strings by their decoded contents, and object members regardless of
+    /// their order.  Arrays must have equal elements in the same order, and a member repeated in
+    /// one object must be repeated as often in the other.  If either value is not valid JSON,
+    /// returns `false`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let a = JSONValue::load(r#"{"a": 1, "b": [true, "x"]}"#);
+    /// let b = JSONValue::load(r#"{"b":[true,"x"],"a":1.0}"#);
+    /// assert!(a.semantic_eq(&b));
+    /// ```
+    pub fn semantic_eq(&self, other: &JSONValue<'_>) -> bool {
+        self.verify().is_ok() && other.verify().is_ok() && self.equals(other)
+    }
+
+    /// Compares this number with `other` numerically
+    ///
+    /// The comparison is exact, however many digits the numbers have, so `10` and `1e1` are equal
+    /// while `0.1` and `0.10000000000000001` are not.  If either value is not a number, or is
+    /// `NaN` and so has no ordering, returns `Err(JSONParsingError::CannotParseFloat)`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// # use core::cmp::Ordering;
+    /// let a = JSONValue::load("9007199254740993");
+    /// let b = JSONValue::load("9007199254740992.5");
+    /// assert_eq!(a.number_cmp(&b), Ok(Ordering::Greater));
+    /// ```
+    pub fn number_cmp(&self, other: &JSONValue<'_>) -> Result<Ordering, JSONParsingError> {
+        let a = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseFloat)?;
+        let b = other
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseFloat)?;
+        // Only the parser's `NaN` and `Infinity` literals contain these letters
+        if a.contains(['N', 'I']) || b.contains(['N', 'I']) {
+            let (a, b) = (self.read_f64()?, other.read_f64()?);
+            return a.partial_cmp(&b).ok_or(JSONParsingError::CannotParseFloat);
+        }
+        Ok(Decimal::new(a).cmp(&Decimal::new(b)))
+    }
+
+    /// Feeds this value into `state`, consistently with [`JSONValue::semantic_eq`]
+    ///
+    /// Values which are semantically equal hash equally, so for example `1` and `1.0` have the
+    /// same hash, as do objects with the same members in a different order.  This allows values to
+    /// be used as keys, through a wrapper type implementing [`Hash`](core::hash::Hash) with this
+    /// and [`PartialEq`] with [`JSONValue::semantic_eq`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// # use std::hash::Hasher;
+    /// let hash = |contents| {
+    ///     let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    ///     JSONValue::load(contents).semantic_hash(&mut hasher);
+    ///     hasher.finish()
+    /// };
+    /// assert_eq!(hash(r#"{"a": 1, "b": 2}"#), hash(r#"{"b": 2.0, "a": 1}"#));
+    /// ```
+    pub fn semantic_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(self.value_type as u8);
+        match self.value_type {
+            JSONValueType::Bool => state.write_u8((self.read_bool() == Ok(true)) as u8),
+            JSONValueType::Number => {
+                // Equal numbers have equal floating point values, aside from the sign of zero
+                let number = self.read_f64().unwrap_or(0.0);
+                let number = if number == 0.0 { 0.0 } else { number };
+                state.write_u64(number.to_bits());
+            }
+            JSONValueType::String => {
+                for chr in self.iter_string().into_iter().flatten() {
+                    state.write_u32(chr.map_or(u32::MAX, u32::from));
+                }
+            }
+            JSONValueType::Array => {
+                let mut len = 0;
+                for item in self.iter_array().into_iter().flatten() {
+                    item.semantic_hash(state);
+                    len += 1;
+                }
+                state.write_usize(len);
+            }
+            JSONValueType::Object => {
+                // Combine the members' hashes in a way that doesn't depend on their order
+                let mut combined = 0u64;
+                for (key, value) in self
+                    .iter_object()
+                    .into_iter()
+                    .flatten()
+                    .map_while(Result::ok)
+                {
+                    let mut member = FnvHasher::new();
+                    for chr in decode_key(key) {
+                        member.write_u32(chr.map_or(u32::MAX, u32::from));
+                    }
+                    member.write_u32(u32::MAX);
+                    value.semantic_hash(&mut member);
+                    combined = combined.wrapping_add(member.finish());
+                }
+                state.write_u64(combined);
+            }
+            JSONValueType::Null | JSONValueType::Error => {}
+        }
+    }
+
+    /// [`JSONValue::semantic_eq`] for values which have already been verified
+    fn equals(&self, other: &JSONValue<'_>) -> bool {
+        if self.value_type != other.value_type {
+            return false;
+        }
+        match self.value_type {
+            JSONValueType::Null => true,
+            JSONValueType::Bool => self.read_bool() == other.read_bool(),
+            JSONValueType::Number => self.number_cmp(other) == Ok(Ordering::Equal),
+            JSONValueType::String => match (self.iter_string(), other.iter_string()) {
+                (Ok(a), Ok(b)) => a.eq(b),
+                _ => false,
+            },
+            JSONValueType::Array => match (self.iter_array(), other.iter_array()) {
+                (Ok(mut a), Ok(mut b)) => loop {
+                    match (a.next(), b.next()) {
+                        (Some(a), Some(b)) if a.equals(&b) => {}
+                        (None, None) => break true,
+                        _ => break false,
+                    }
+                },
+                _ => false,
+            },
+            JSONValueType::Object => self.same_members(other),
+            JSONValueType::Error => false,
+        }
+    }
+
+    /// Whether both objects have the same members, each appearing the same number of times
+    ///
+    /// Counting repeated members, rather than only checking each has a match, keeps this
+    /// consistent with [`JSONValue::semantic_hash`], which adds up a hash of every member.
+    fn same_members(&self, other: &JSONValue<'_>) -> bool {
+        // The number of members of `object` equal to `key: value`
+        fn count(object: &JSONValue<'_>, key: &str, value: &JSONValue<'_>) -> Option<usize> {
+            let mut count = 0;
+            for member in object.iter_object().ok()? {
+                let (other_key, other_value) = member.ok()?;
+                if decode_key(key).eq(decode_key(other_key)) && value.equals(&other_value) {
+                    count += 1;
+                }
+            }
+            Some(count)
+        }
+
+        if self.object_len().ok() != other.object_len().ok() {
+            return false;
+        }
+        let mut members = match self.iter_object() {
+            Ok(members) => members,
+            Err(_) => return false,
+        };
+        members.all(|member| match member {
+            Ok((key, value)) => {
+                let count_here = count(self, key, &value);
+                count_here.is_some() && count_here == count(other, key, &value)
+            }
+            Err(_) => false,
+        })
+    }
+}
+
+/// Compares a string value with `other` by its decoded contents
+///
+/// Values which are not valid strings compare unequal to every `str`.
+///
+/// ### Example
+/// ```
+/// # use microjson::JSONValue;
+/// assert!(JSONValue::load(r#""caf\u00e9""#) == "café");
+/// assert!(JSONValue::load("42") != "42");
+/// ```
+impl PartialEq<str> for JSONValue<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.read_string_raw().is_ok()
+            && self
+                .iter_string()
+                .is_ok_and(|chars| chars.eq(other.chars().map(Ok)))
+    }
+}
+
+impl PartialEq<&str> for JSONValue<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// A number split into its sign, significant digits and the position of the first of those
+struct Decimal<'a> {
+    negative: bool,
+    /// The digits before and after the decimal point
+    integer: &'a str,
+    fraction: &'a str,
+    /// How many leading zeros there are in `integer` followed by `fraction`
+    leading_zeros: usize,
+    /// The power of ten just above the first significant digit, or `None` for zero
+    magnitude: Option<i64>,
+}
+
+impl<'a> Decimal<'a> {
+    /// Splits up a number, which must follow the JSON number grammar (leniently or not)
+    fn new(text: &'a str) -> Decimal<'a> {
+        let negative = text.starts_with('-');
+        let text = text.trim_start_matches(['-', '+']);
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(index) => (&text[..index], &text[index + 1..]),
+            None => (text, ""),
+        };
+        let (integer, fraction) = match mantissa.find('.') {
+            Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+            None => (mantissa, ""),
+        };
+
+        // Exponents too large to represent are rounded towards zero, to a point where no number
+        // could have enough digits for it to make a difference
+        let exponent_negative = exponent.starts_with('-');
+        let exponent = exponent
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .fold(0i64, |value, digit| {
+                value
+                    .saturating_mul(10)
+                    .saturating_add(i64::from(digit - b'0'))
+            })
+            .min(i64::MAX / 4);
+        let exponent = if exponent_negative {
+            -exponent
+        } else {
+            exponent
+        };
+
+        let leading_zeros = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .take_while(|&digit| digit == b'0')
+            .count();
+        let magnitude = if leading_zeros == integer.len() + fraction.len() {
+            None
+        } else {
+            Some(integer.len() as i64 - leading_zeros as i64 + exponent)
+        };
+        Decimal {
+            negative,
+            integer,
+            fraction,
+            leading_zeros,
+            magnitude,
+        }
+    }
+
+    /// The significant digits, from the first non-zero digit onwards
+    fn digits(&self) -> impl Iterator<Item = u8> + 'a {
+        self.integer
+            .bytes()
+            .chain(self.fraction.bytes())
+            .skip(self.leading_zeros)
+    }
+
+    /// Compares the absolute values of two non-zero numbers
+    fn cmp_magnitude(&self, other: &Decimal<'_>) -> Ordering {
+        self.magnitude.cmp(&other.magnitude).then_with(|| {
+            let (mut a, mut b) = (self.digits(), other.digits());
+            loop {
+                match (a.next(), b.next()) {
+                    (Some(x), Some(y)) if x == y => {}
+                    (Some(x), Some(y)) => return x.cmp(&y),
+                    // Trailing zeros don't change the value
+                    (Some(_), None) if a.all(|digit| digit == b'0') => return Ordering::Equal,
+                    (Some(_), None) => return Ordering::Greater,
+                    (None, Some(_)) if b.all(|digit| digit == b'0') => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (None, None) => return Ordering::Equal,
+                }
+            }
+        })
+    }
+
+    fn cmp(&self, other: &Decimal<'_>) -> Ordering {
+        match (self.magnitude, other.magnitude) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) if other.negative => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) if self.negative => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(_), Some(_)) => match (self.negative, other.negative) {
+                (false, true) => Ordering::Greater,
+                (true, false) => Ordering::Less,
+                (false, false) => self.cmp_magnitude(other),
+                (true, true) => other.cmp_magnitude(self),
+            },
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash, for hashing object members independently of the caller's hasher
+struct FnvHasher(u64);
+
+impl FnvHasher {
+    fn new() -> FnvHasher {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    extern crate std;
+
+    fn hash(contents: &str) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        JSONValue::load(contents).semantic_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn number_comparison() {
+        let cmp = |a, b| JSONValue::load(a).number_cmp(&JSONValue::load(b));
+        for (a, b, ordering) in [
+            ("-0", "0", Ordering::Equal),
+            ("0", "0.000e5", Ordering::Equal),
+            ("10", "1e1", Ordering::Equal),
+            ("1.50", "15e-1", Ordering::Equal),
+            ("0.001", "1E-3", Ordering::Equal),
+            ("1", "2", Ordering::Less),
+            ("-1", "-2", Ordering::Greater),
+            ("-1", "0", Ordering::Less),
+            ("0", "-0.5", Ordering::Greater),
+            ("-3", "2", Ordering::Less),
+            ("99", "100", Ordering::Less),
+            ("0.25", "0.3", Ordering::Less),
+            ("1.0000001", "1", Ordering::Greater),
+            ("0.1", "0.10000000000000001", Ordering::Less),
+            (
+                "18446744073709551616",
+                "18446744073709551615",
+                Ordering::Greater,
+            ),
+            (
+                "-9223372036854775809",
+                "-9223372036854775808",
+                Ordering::Less,
+            ),
+            ("1e400", "2e400", Ordering::Less),
+            ("1e-400", "0", Ordering::Greater),
+            ("1e99999999999999999999", "1e400", Ordering::Greater),
+        ] {
+            assert_eq!(cmp(a, b), Ok(ordering), "{} {}", a, b);
+            assert_eq!(cmp(b, a), Ok(ordering.reverse()), "{} {}", b, a);
+        }
+        assert_eq!(cmp("1", "\"1\""), Err(JSONParsingError::CannotParseFloat));
+        assert_eq!(cmp("[]", "1"), Err(JSONParsingError::CannotParseFloat));
+        assert_eq!(cmp("01", "1"), Err(JSONParsingError::CannotParseFloat));
+    }
+
+    #[test]
+    fn semantic_hashing() {
+        for (a, b) in [
+            (r#"{"a":1,"b":2}"#, r#"{"b":2,"a":1}"#),
+            ("1", "1.0"),
+            ("-0", "0.0"),
+            ("100", "1e2"),
+            (r#""\u0061b""#, r#""ab""#),
+            ("[1, {\"x\": [true, null]}]", "[1.0,{\"x\":[true,null]}]"),
+            (
+                r#"{"\u0061": {"c": 1, "d": 2}}"#,
+                r#"{"a": {"d": 2, "c": 1}}"#,
+            ),
+            (r#"{"a":1,"a":2}"#, r#"{"a":2.0,"\u0061":1}"#),
+        ] {
+            assert!(JSONValue::load(a).semantic_eq(&JSONValue::load(b)));
+            assert_eq!(hash(a), hash(b), "{} {}", a, b);
+        }
+
+        // Not required, but the hash would be fairly useless if these collided
+        for (a, b) in [
+            ("1", "2"),
+            ("true", "false"),
+            ("[1, 2]", "[2, 1]"),
+            ("[[1], 2]", "[[1, 2]]"),
+            (r#"{"a": 1}"#, r#"{"a": 2}"#),
+            (r#"{"a": 1, "b": 2}"#, r#"{"a": 2, "b": 1}"#),
+            (r#""ab""#, r#""ba""#),
+            ("null", "[]"),
+        ] {
+            assert_ne!(hash(a), hash(b), "{} {}", a, b);
+        }
+
+        // Repeated members count towards both equality and the hash
+        for (a, b) in [
+            (r#"{"a":1,"a":1}"#, r#"{"a":1}"#),
+            (r#"{"a":1,"a":1,"b":2}"#, r#"{"a":1,"b":2,"b":2}"#),
+        ] {
+            assert!(!JSONValue::load(a).semantic_eq(&JSONValue::load(b)));
+            assert_ne!(hash(a), hash(b), "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn semantic_equality() {
+        let eq = |a, b| JSONValue::load(a).semantic_eq(&JSONValue::load(b));
+
+        assert!(eq("1", "1.0"));
+        assert!(eq("100", "1e2"));
+        assert!(eq("-0", "0"));
+        assert!(eq("0.5", "5E-1"));
+        assert!(eq("18446744073709551615", "18446744073709551615"));
+        assert!(!eq("18446744073709551615", "18446744073709551614"));
+        assert!(!eq("1", "2"));
+
+        assert!(eq(r#""abc""#, r#""abc""#));
+        assert!(!eq(r#""abc""#, r#""abd""#));
+        assert!(eq("true", " true "));
+        assert!(!eq("true", "false"));
+        assert!(eq("null", "null"));
+        assert!(!eq("null", "0"));
+        assert!(!eq("1", "\"1\""));
+
+        assert!(eq("[1, [2, 3]]", "[ 1 ,[2,3.0] ]"));
+        assert!(!eq("[1, 2]", "[2, 1]"));
+        assert!(!eq("[1, 2]", "[1, 2, 3]"));
+        assert!(!eq("[1, 2, 3]", "[1, 2]"));
+        assert!(eq("[]", "[ ]"));
+
+        assert!(eq(r#"{"a": 1, "b": 2}"#, r#"{"b": 2, "a": 1}"#));
+        assert!(eq(
+            "{\n  \"a\": {\"x\": [1, {}]},\n  \"b\": null\n}",
+            r#"{"b":null,"a":{"x":[1.0,{}]}}"#
+        ));
+        assert!(!eq(r#"{"a": 1, "b": 2}"#, r#"{"a": 1}"#));
+        assert!(!eq(r#"{"a": 1}"#, r#"{"a": 1, "b": 2}"#));
+        assert!(!eq(r#"{"a": 1}"#, r#"{"a": 2}"#));
+        assert!(!eq(r#"{"a": 1}"#, "[1]"));
+
+        // Malformed values are never equal, even to themselves
+        assert!(!eq("[1, x]", "[1, x]"));
+        assert!(!eq("{", "{"));
+    }
+
+    #[test]
+    fn string_equality() {
+        let value = JSONValue::load(r#""hello""#);
+        assert!(value == "hello");
+        assert!(value != "bye");
+        assert!(value != "hell");
+        assert!(value == *"hello");
+        assert!(JSONValue::load(r#""tab\there""#) == "tab\there");
+        assert!(JSONValue::load(r#""\ud83d\ude00""#) == "\u{1f600}");
+        assert!(JSONValue::load("42") != "42");
+        assert!(JSONValue::load(r#"["hello"]"#) != "hello");
+        assert!(JSONValue::load(r#""hello"#) != "hello");
+        assert!(JSONValue::load(r#""bad \x""#) != "bad \\x");
+    }
+}
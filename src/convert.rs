@@ -0,0 +1,77 @@
+use crate::{JSONParsingError, JSONValue};
+use core::convert::TryFrom;
+
+/// Reads the value with [`JSONValue::read_i64`]
+impl<'a> TryFrom<JSONValue<'a>> for i64 {
+    type Error = JSONParsingError;
+
+    fn try_from(value: JSONValue<'a>) -> Result<i64, JSONParsingError> {
+        value.read_i64()
+    }
+}
+
+/// Reads the value with [`JSONValue::read_f64`]
+impl<'a> TryFrom<JSONValue<'a>> for f64 {
+    type Error = JSONParsingError;
+
+    fn try_from(value: JSONValue<'a>) -> Result<f64, JSONParsingError> {
+        value.read_f64()
+    }
+}
+
+/// Reads the value with [`JSONValue::read_bool`]
+impl<'a> TryFrom<JSONValue<'a>> for bool {
+    type Error = JSONParsingError;
+
+    fn try_from(value: JSONValue<'a>) -> Result<bool, JSONParsingError> {
+        value.read_bool()
+    }
+}
+
+/// Reads the value with [`JSONValue::read_string`]
+///
+/// Like that method, this gives the raw contents of the string, with any escape sequences left
+/// as they are.
+impl<'a> TryFrom<JSONValue<'a>> for &'a str {
+    type Error = JSONParsingError;
+
+    fn try_from(value: JSONValue<'a>) -> Result<&'a str, JSONParsingError> {
+        value.read_string_raw()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::convert::TryInto;
+
+    #[test]
+    fn conversions() {
+        let value = JSONValue::load(r#"[9000000000, 2.5, true, "a\nb"]"#);
+        let item = |n| value.get_nth_array_item(n).unwrap();
+
+        assert_eq!(i64::try_from(item(0)), Ok(9_000_000_000));
+        assert_eq!(f64::try_from(item(1)), Ok(2.5));
+        assert_eq!(bool::try_from(item(2)), Ok(true));
+        assert_eq!(<&str>::try_from(item(3)), Ok(r"a\nb"));
+        let integer: Result<i64, _> = item(0).try_into();
+        assert_eq!(integer, Ok(9_000_000_000));
+
+        assert_eq!(
+            i64::try_from(item(1)),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            f64::try_from(item(3)),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+        assert_eq!(
+            bool::try_from(item(0)),
+            Err(JSONParsingError::CannotParseBool)
+        );
+        assert_eq!(
+            <&str>::try_from(item(2)),
+            Err(JSONParsingError::CannotParseString)
+        );
+    }
+}
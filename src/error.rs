@@ -6,6 +6,8 @@
 pub enum JSONParsingError {
     /// Attempt to parse an object that is not an array as an array
     CannotParseArray,
+    /// Attempt to parse an object that is not a boolean as a boolean
+    CannotParseBool,
     /// Attempt to parse an object that is not a float as a float
     CannotParseFloat,
     /// Attempt to parse an object that is not an integer as an integer
@@ -14,12 +16,32 @@ pub enum JSONParsingError {
     CannotParseObject,
     /// Attempt to parse an object that is not a string as an string
     CannotParseString,
+    /// Attempt to treat a value that is neither an array nor an object as a container
+    NotAContainer,
     /// The key is not present in the object
     KeyNotFound,
+    /// The index is past the end of the array
+    IndexOutOfBounds,
+    /// The same key appears more than once in an object
+    DuplicateKey,
+    /// A JSON Pointer is not well formed
+    InvalidPointer,
+    /// The input is not valid UTF-8
+    InvalidUtf8,
     /// There was an unexpected token in the input stream
     UnexpectedToken,
     /// The input stream terminated while scanning a type
     EndOfStream,
+    /// A number does not follow the JSON number grammar
+    InvalidNumber,
+    /// A number is too large for the type it is being read as
+    NumberOverflow,
+    /// A string contains a control character which has not been escaped
+    InvalidStringCharacter,
+    /// Arrays and objects are nested more deeply than the parser allows
+    DepthLimitExceeded,
+    /// A buffer is too small to hold the decoded value
+    BufferTooSmall,
     /// Escape sequence too short (all escape sequences must be four hex digits long)
     TooShortEscapeSequence,
     /// Escape sequence doesn't map to a character
@@ -34,15 +56,42 @@ impl core::fmt::Display for JSONParsingError {
             Self::KeyNotFound => {
                 write!(f, "key not found")
             }
+            Self::DuplicateKey => {
+                write!(f, "duplicate key in object")
+            }
+            Self::InvalidPointer => {
+                write!(f, "invalid JSON pointer")
+            }
+            Self::IndexOutOfBounds => {
+                write!(f, "index out of bounds")
+            }
             Self::EndOfStream => {
                 write!(f, "stream ended while parsing JSON")
             }
+            Self::InvalidNumber => {
+                write!(f, "invalid number")
+            }
+            Self::NumberOverflow => {
+                write!(f, "number out of range")
+            }
+            Self::InvalidStringCharacter => {
+                write!(f, "unescaped control character in string")
+            }
+            Self::DepthLimitExceeded => {
+                write!(f, "nesting depth limit exceeded")
+            }
+            Self::InvalidUtf8 => {
+                write!(f, "invalid UTF-8")
+            }
             Self::UnexpectedToken => {
                 write!(f, "unexpected token")
             }
             Self::CannotParseArray => {
                 write!(f, "error parsing array")
             }
+            Self::CannotParseBool => {
+                write!(f, "error parsing bool")
+            }
             Self::CannotParseFloat => {
                 write!(f, "error parsing float")
             }
@@ -55,6 +104,12 @@ impl core::fmt::Display for JSONParsingError {
             Self::CannotParseObject => {
                 write!(f, "error parsing object")
             }
+            Self::NotAContainer => {
+                write!(f, "value is not an array or object")
+            }
+            Self::BufferTooSmall => {
+                write!(f, "buffer too small")
+            }
             Self::TooShortEscapeSequence => {
                 write!(f, "escape sequence fewer than four digits")
             }
@@ -68,6 +123,81 @@ impl core::fmt::Display for JSONParsingError {
     }
 }
 
+/// A [`JSONParsingError`] along with where in the input it occurred
+///
+/// This is returned by functions that scan through a payload, such as [`JSONValue::verify`], so
+/// that malformed input can be tracked down.  It converts into a plain [`JSONParsingError`] for
+/// use with `?`.
+///
+/// [`JSONValue::verify`]: crate::JSONValue::verify
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct JSONLocatedError {
+    /// The kind of error encountered
+    pub kind: JSONParsingError,
+    /// The byte offset in the input at which the error was detected
+    pub offset: usize,
+}
+
+impl JSONLocatedError {
+    pub(crate) fn shifted(self, by: usize) -> JSONLocatedError {
+        JSONLocatedError {
+            kind: self.kind,
+            offset: self.offset + by,
+        }
+    }
+
+    /// The 1-based line and column of this error within `input`
+    ///
+    /// `input` must be the payload that produced this error.  See [`line_col`].
+    pub fn line_col(&self, input: &str) -> (usize, usize) {
+        line_col(input, self.offset)
+    }
+}
+
+/// Converts a byte offset in `input` into a 1-based line and column
+///
+/// Lines may be terminated by `\n`, `\r\n` or a lone `\r`.  Columns count characters, so a tab
+/// or a multi-byte character each take up a single column.
+///
+/// ### Example
+/// ```
+/// # use microjson::line_col;
+/// assert_eq!(line_col("[\r\n  1,\r\n  x\r\n]", 11), (3, 3));
+/// ```
+pub fn line_col(input: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    let mut chars = input.char_indices().peekable();
+    while let Some((index, chr)) = chars.next() {
+        if index >= offset {
+            break;
+        }
+        match chr {
+            '\r' if matches!(chars.peek(), Some((_, '\n'))) => {}
+            '\n' | '\r' => {
+                line += 1;
+                column = 1;
+            }
+            _ => {
+                column += 1;
+            }
+        }
+    }
+    (line, column)
+}
+
+impl From<JSONLocatedError> for JSONParsingError {
+    fn from(error: JSONLocatedError) -> JSONParsingError {
+        error.kind
+    }
+}
+
+impl core::fmt::Display for JSONLocatedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -79,17 +209,57 @@ mod test {
         // This is mostly to check the formatting doesn't crash or overlap, rather than the format exactly
         let mut messages = std::collections::HashSet::new();
         messages.insert(JSONParsingError::CannotParseArray.to_string());
+        messages.insert(JSONParsingError::CannotParseBool.to_string());
         messages.insert(JSONParsingError::CannotParseFloat.to_string());
         messages.insert(JSONParsingError::CannotParseInteger.to_string());
         messages.insert(JSONParsingError::CannotParseObject.to_string());
         messages.insert(JSONParsingError::CannotParseString.to_string());
+        messages.insert(JSONParsingError::NotAContainer.to_string());
         messages.insert(JSONParsingError::KeyNotFound.to_string());
+        messages.insert(JSONParsingError::IndexOutOfBounds.to_string());
+        messages.insert(JSONParsingError::DuplicateKey.to_string());
+        messages.insert(JSONParsingError::InvalidPointer.to_string());
+        messages.insert(JSONParsingError::InvalidUtf8.to_string());
         messages.insert(JSONParsingError::UnexpectedToken.to_string());
         messages.insert(JSONParsingError::EndOfStream.to_string());
+        messages.insert(JSONParsingError::InvalidNumber.to_string());
+        messages.insert(JSONParsingError::NumberOverflow.to_string());
+        messages.insert(JSONParsingError::InvalidStringCharacter.to_string());
+        messages.insert(JSONParsingError::DepthLimitExceeded.to_string());
+        messages.insert(JSONParsingError::BufferTooSmall.to_string());
         messages.insert(JSONParsingError::TooShortEscapeSequence.to_string());
         messages.insert(JSONParsingError::InvalidUnicodeEscapeSequence.to_string());
         messages.insert(JSONParsingError::InvalidEscapeSequence('q').to_string());
         messages.insert(JSONParsingError::InvalidEscapeSequence('v').to_string());
-        assert_eq!(messages.len(), 12);
+        assert_eq!(messages.len(), 23);
+    }
+
+    #[test]
+    fn located_error_formatting() {
+        let error = JSONLocatedError {
+            kind: JSONParsingError::UnexpectedToken,
+            offset: 12,
+        };
+        assert_eq!(error.to_string(), "unexpected token at byte 12");
+        assert_eq!(
+            JSONParsingError::from(error),
+            JSONParsingError::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn line_and_column() {
+        assert_eq!(line_col("", 0), (1, 1));
+        assert_eq!(line_col("abc", 2), (1, 3));
+        assert_eq!(line_col("a\nb\nc", 4), (3, 1));
+        assert_eq!(line_col("a\r\nb\r\n\tc", 7), (3, 2));
+        assert_eq!(line_col("a\rb\rc", 4), (3, 1));
+        assert_eq!(line_col("\u{e9}\u{e9}x", 4), (1, 3));
+        assert_eq!(line_col("ab", 10), (1, 3));
+
+        let input = "{\r\n  \"a\": 1,\r\n\t\"b\": ?\r\n}";
+        let error = crate::JSONValue::load_and_verify(input).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.line_col(input), (3, 7));
     }
 }
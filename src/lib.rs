@@ -17,8 +17,20 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod compare;
+mod convert;
 mod error;
-pub use error::JSONParsingError;
+mod parser;
+mod path;
+mod write;
+pub use error::{line_col, JSONLocatedError, JSONParsingError};
+use parser::NestingStack;
+pub use parser::{Parser, DEFAULT_MAX_DEPTH, MAX_NESTING_DEPTH};
+pub use path::Segment;
+pub use write::{escape_str_into, JSONWriter};
 
 /// Denotes the different types of values JSON objects can have
 ///
@@ -46,47 +58,326 @@ pub enum JSONValueType {
 pub struct JSONValue<'a> {
     contents: &'a str,
     pub value_type: JSONValueType,
+    parser: Parser,
 }
 
-fn trim_start(value: &str) -> (&str, usize) {
+/// Skips the whitespace at the start of `value`, returning the rest along with how much was skipped
+///
+/// Only the whitespace allowed by the JSON grammar is skipped, unless the parser allows any
+/// Unicode whitespace.  Comments are skipped too if the parser allows them.
+fn trim_start(value: &str, parser: Parser) -> (&str, usize) {
     let value_len = value.len();
-    // NOTE(robert): This trims from the "start" which may be different for RTL languages.  What do
-    // we do for JSON?
-    let value = value.trim_start();
+    let mut value = value;
+    loop {
+        // NOTE(robert): This trims from the "start" which may be different for RTL languages.
+        // What do we do for JSON?
+        value = if parser.allow_unicode_whitespace {
+            value.trim_start()
+        } else {
+            value.trim_start_matches([' ', '\t', '\n', '\r'])
+        };
+        if !parser.allow_comments {
+            break;
+        }
+        if let Some(comment) = value.strip_prefix("//") {
+            let end = comment.find(['\n', '\r']).unwrap_or(comment.len());
+            value = &comment[end..];
+        } else if let Some(end) = value
+            .strip_prefix("/*")
+            .and_then(|comment| comment.find("*/"))
+        {
+            value = &value[end + 4..];
+        } else {
+            // An unterminated block comment is left to be reported as an unexpected token
+            break;
+        }
+    }
     (value, value_len - value.len())
 }
 
+/// The characters of an object key as returned by [`JSONObjectIterator`], with escapes resolved
+fn decode_key(key: &str) -> impl Iterator<Item = Result<char, JSONParsingError>> + '_ {
+    // The key's closing quote has been stripped, so the end of the key is the end of the stream.
+    // Control characters are never part of a key, so the NUL quote never ends it early.
+    EscapedStringIterator {
+        contents: key.chars(),
+        quote: '\0',
+        done: false,
+    }
+    .take_while(|chr| *chr != Err(JSONParsingError::EndOfStream))
+}
+
+/// Scans an object key and the following colon, starting at `position` in `contents`
+///
+/// Returns the position of the member's value.
+fn scan_key(
+    contents: &str,
+    mut position: usize,
+    parser: Parser,
+) -> Result<usize, JSONLocatedError> {
+    let error = |kind, offset| JSONLocatedError { kind, offset };
+    let rest = &contents[position..];
+    if rest.is_empty() {
+        return Err(error(JSONParsingError::EndOfStream, position));
+    } else if parser.allow_unquoted_keys && identifier_len(rest) > 0 {
+        position += identifier_len(rest);
+    } else if is_string_start(rest, parser) {
+        position += scan_string(rest).map_err(|e| e.shifted(position))?;
+    } else {
+        return Err(error(JSONParsingError::UnexpectedToken, position));
+    }
+    position += trim_start(&contents[position..], parser).1;
+    let rest = &contents[position..];
+    if rest.is_empty() {
+        return Err(error(JSONParsingError::EndOfStream, position));
+    } else if !rest.starts_with(':') {
+        return Err(error(JSONParsingError::UnexpectedToken, position));
+    }
+    Ok(position + 1 + trim_start(&rest[1..], parser).1)
+}
+
+/// Writes decoded characters into `buf` as UTF-8, returning the number of bytes written
+///
+/// Only whole characters are written, so if `buf` fills up what was written is still valid.
+fn decode_into(
+    chars: impl Iterator<Item = Result<char, JSONParsingError>>,
+    buf: &mut [u8],
+) -> Result<usize, JSONParsingError> {
+    let mut written = 0;
+    for chr in chars {
+        let chr = chr?;
+        let target = buf
+            .get_mut(written..written + chr.len_utf8())
+            .ok_or(JSONParsingError::BufferTooSmall)?;
+        chr.encode_utf8(target);
+        written += chr.len_utf8();
+    }
+    Ok(written)
+}
+
+/// Whether `contents` starts with a quote that may open a string under `parser`
+fn is_string_start(contents: &str, parser: Parser) -> bool {
+    contents.starts_with('"') || (parser.allow_single_quotes && contents.starts_with('\''))
+}
+
+/// The length of the identifier at the start of `contents`, or zero if there isn't one
+///
+/// Identifiers are made up of letters, digits, `_` and `$`, but don't start with a digit.
+fn identifier_len(contents: &str) -> usize {
+    let is_identifier = |chr: char| chr.is_alphanumeric() || chr == '_' || chr == '$';
+    if contents.starts_with(|chr: char| chr.is_numeric()) {
+        return 0;
+    }
+    contents
+        .find(|chr| !is_identifier(chr))
+        .unwrap_or(contents.len())
+}
+
+/// The length of the string at the start of `contents`, including its quotes
+///
+/// The string ends at the next unescaped quote matching the opening one.  Control characters
+/// must be escaped within strings, so are rejected, as are escape sequences which can't be
+/// decoded.
+fn scan_string(contents: &str) -> Result<usize, JSONLocatedError> {
+    let quote = contents.chars().next();
+    let mut value_len = 1;
+    let mut is_escaped = false;
+    for chr in contents[1..].chars() {
+        if chr < '\u{20}' {
+            return Err(JSONLocatedError {
+                kind: JSONParsingError::InvalidStringCharacter,
+                offset: value_len,
+            });
+        }
+        value_len += chr.len_utf8();
+        if Some(chr) == quote && !is_escaped {
+            check_escapes(&contents[..value_len])?;
+            return Ok(value_len);
+        } else if chr == '\\' {
+            is_escaped = !is_escaped;
+        } else {
+            is_escaped = false;
+        }
+    }
+    Err(JSONLocatedError {
+        kind: JSONParsingError::EndOfStream,
+        offset: value_len,
+    })
+}
+
+/// Checks that every escape sequence in `string`, including its quotes, can be decoded
+///
+/// Errors are located at the start of the offending escape sequence.
+fn check_escapes(string: &str) -> Result<(), JSONLocatedError> {
+    if !string.contains('\\') {
+        return Ok(());
+    }
+    let mut chars = EscapedStringIterator {
+        contents: string[1..].chars(),
+        quote: string.chars().next().unwrap_or('"'),
+        done: false,
+    };
+    loop {
+        let offset = string.len() - chars.contents.as_str().len();
+        match chars.next() {
+            None => return Ok(()),
+            Some(Ok(_)) => {}
+            Some(Err(kind)) => return Err(JSONLocatedError { kind, offset }),
+        }
+    }
+}
+
+/// An iterator over the tokens of a valid JSON value, skipping whitespace
+///
+/// Each token is a punctuation character, a string including its quotes, a number or a literal.
+struct Tokens<'a> {
+    contents: &'a str,
+    parser: Parser,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (contents, _) = trim_start(self.contents, self.parser);
+        let token_len = match contents.chars().next()? {
+            '{' | '}' | '[' | ']' | ',' | ':' | '/' => 1,
+            '"' | '\'' => scan_string(contents).unwrap_or(contents.len()),
+            _ => contents
+                .find(|chr: char| "{}[],:\"/".contains(chr) || chr.is_whitespace())
+                .unwrap_or(contents.len()),
+        };
+        let (token, rest) = contents.split_at(token_len);
+        self.contents = rest;
+        Some(token)
+    }
+}
+
+/// Converts an error reading an integer accessor's number into a [`JSONParsingError`]
+fn integer_error(error: core::num::ParseIntError) -> JSONParsingError {
+    match error.kind() {
+        core::num::IntErrorKind::PosOverflow | core::num::IntErrorKind::NegOverflow => {
+            JSONParsingError::NumberOverflow
+        }
+        _ => JSONParsingError::CannotParseInteger,
+    }
+}
+
+/// The length of the number at the start of `contents`
+///
+/// Numbers must follow the JSON grammar: an optional minus sign, an integer part without leading
+/// zeros, then an optional fraction and an optional exponent.  The parser may also allow a leading
+/// plus sign, a fraction without an integer part, and the literals `NaN` and `Infinity`.
+fn scan_number(contents: &str, parser: Parser) -> Result<usize, JSONLocatedError> {
+    let bytes = contents.as_bytes();
+    let digits_from = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+    let invalid = |offset| {
+        Err(JSONLocatedError {
+            kind: JSONParsingError::InvalidNumber,
+            offset,
+        })
+    };
+
+    let mut value_len = match bytes.first() {
+        Some(b'-') => 1,
+        Some(b'+') if parser.allow_lenient_numbers => 1,
+        _ => 0,
+    };
+    if parser.allow_nan_and_infinity {
+        for literal in ["NaN", "Infinity"] {
+            if contents[value_len..].starts_with(literal) {
+                return Ok(value_len + literal.len());
+            }
+        }
+    }
+    match digits_from(value_len) {
+        0 if parser.allow_lenient_numbers && bytes.get(value_len) == Some(&b'.') => {}
+        0 => return invalid(value_len),
+        digits if digits > 1 && bytes[value_len] == b'0' => return invalid(value_len + 1),
+        digits => value_len += digits,
+    }
+    if bytes.get(value_len) == Some(&b'.') {
+        match digits_from(value_len + 1) {
+            0 => return invalid(value_len + 1),
+            digits => value_len += 1 + digits,
+        }
+    }
+    if matches!(bytes.get(value_len), Some(b'e' | b'E')) {
+        value_len += 1;
+        if matches!(bytes.get(value_len), Some(b'+' | b'-')) {
+            value_len += 1;
+        }
+        match digits_from(value_len) {
+            0 => return invalid(value_len),
+            digits => value_len += digits,
+        }
+    }
+    // Anything else which could be part of a number means it was malformed
+    if matches!(bytes.get(value_len), Some(b'-' | b'+' | b'.' | b'e' | b'E')) {
+        return invalid(value_len);
+    }
+    Ok(value_len)
+}
+
 impl<'a> JSONValue<'a> {
     /// Create a new `JSONValue` from an input string
     ///
     /// This is the primary method of constructing a [`JSONValue`]. It cannot fail, although the
     /// value might have type [`JSONValueType::Error`]. However, a malformed payload may have a
-    /// type that is not `JSONValueType::Error`.
+    /// type that is not `JSONValueType::Error`.  A UTF-8 byte order mark at the start of the
+    /// payload is skipped.
     ///
     /// If you want to load the payload and verify that it is valid JSON, use
     /// [`JSONValue::load_and_verify`].
-    pub fn load(contents: &'a str) -> JSONValue {
-        let (contents, _) = trim_start(contents);
-        let value_type = JSONValue::peek_value_type(contents);
-        JSONValue {
-            contents,
-            value_type,
+    pub fn load(contents: &'a str) -> JSONValue<'a> {
+        Parser::new().load(contents)
+    }
+
+    /// The type of the value at the start of `contents`, judged from its first character
+    ///
+    /// Leading whitespace is skipped, but the rest of the value is not checked, so this is much
+    /// cheaper than parsing it.  If `contents` is empty or its first character can't start a JSON
+    /// value, returns an error.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONValueType};
+    /// assert_eq!(JSONValue::peek_type(r#" {"kind": "ping"}"#), Ok(JSONValueType::Object));
+    /// assert!(JSONValue::peek_type("<html>").is_err());
+    /// ```
+    pub fn peek_type(contents: &str) -> Result<JSONValueType, JSONParsingError> {
+        let (contents, _) = trim_start(contents, Parser::new());
+        match contents.chars().next() {
+            None => Err(JSONParsingError::EndOfStream),
+            Some(_) => match JSONValue::peek_value_type(contents, Parser::new()) {
+                JSONValueType::Error => Err(JSONParsingError::UnexpectedToken),
+                value_type => Ok(value_type),
+            },
         }
     }
 
     /// Guess the type of the JSON variable serialised in the input string
     ///
     /// This function will never give the _wrong_ type, though it may return a type even if the
-    /// input string is not well formed.
-    fn peek_value_type(contents: &'a str) -> JSONValueType {
+    /// input string is not well formed.  Characters which only start a value with one of the
+    /// parser's extensions to JSON are only recognised if `parser` allows that extension.
+    fn peek_value_type(contents: &'a str, parser: Parser) -> JSONValueType {
         // The contents must be trimmed
         match contents.chars().next() {
             Some('{') => JSONValueType::Object,
             Some('[') => JSONValueType::Array,
             Some('"') => JSONValueType::String,
+            Some('\'') if parser.allow_single_quotes => JSONValueType::String,
             Some('0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '-') => {
                 JSONValueType::Number
             }
+            Some('+' | '.') if parser.allow_lenient_numbers => JSONValueType::Number,
+            Some('N' | 'I') if parser.allow_nan_and_infinity => JSONValueType::Number,
             Some('t' | 'f') => JSONValueType::Bool,
             Some('n') => JSONValueType::Null,
             _ => JSONValueType::Error,
@@ -98,6 +389,8 @@ impl<'a> JSONValue<'a> {
     /// This will scan through the entire JSON and confirm that it is properly formatted.
     /// See also [`JSONValue::load_and_verify`].
     ///
+    /// On failure, the error's offset is relative to the start of this value.
+    ///
     /// ## Example
     /// ```
     /// # use microjson::JSONValue;
@@ -107,143 +400,349 @@ impl<'a> JSONValue<'a> {
     /// let value = JSONValue::load("[,,{\"");
     /// assert!(value.verify().is_err());
     /// ```
-    pub fn verify(&self) -> Result<(), JSONParsingError> {
-        JSONValue::parse_with_len(self.contents)?;
+    pub fn verify(&self) -> Result<(), JSONLocatedError> {
+        JSONValue::parse_with_len(self.contents, self.parser)?;
         Ok(())
     }
 
     /// Load a JSON value from a payload and verify that it is valid JSON.
     ///
     /// This is equivalent to calling [`JSONValue::load`] followed by [`JSONValue::verify`].
-    pub fn load_and_verify(contents: &'a str) -> Result<JSONValue, JSONParsingError> {
-        let value = JSONValue::load(contents);
-        value.verify()?;
-        Ok(value)
-    }
-
-    fn parse_with_len(contents: &'a str) -> Result<(JSONValue, usize), JSONParsingError> {
-        let (contents, whitespace_trimmed) = trim_start(contents);
-        let (value_type, value_len) = match contents.chars().next() {
-            Some('{') => {
-                let mut value_len = 1;
-                let mut contents = &contents[value_len..];
-                while !contents.is_empty() {
-                    if contents.trim_start().starts_with('}') {
-                        value_len += trim_start(contents).1 + 1;
-                        break;
-                    }
-                    let (item, item_len) = JSONValue::parse_with_len(contents)?;
-                    if item.value_type != JSONValueType::String {
-                        return Err(JSONParsingError::CannotParseString);
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let error = JSONValue::load_and_verify("[1, 2, }").unwrap_err();
+    /// assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+    /// assert_eq!(error.offset, 7);
+    /// ```
+    pub fn load_and_verify(contents: &'a str) -> Result<JSONValue<'a>, JSONLocatedError> {
+        Parser::new().load_and_verify(contents)
+    }
+
+    /// Parse a single value from the start of `contents`, verifying that it is valid JSON
+    ///
+    /// Returns the value along with the number of bytes consumed, including leading whitespace and
+    /// any UTF-8 byte order mark at the very start.  Any content after the value is ignored; use
+    /// [`JSONValue::parse_exact`] to reject it.  Errors report their offset relative to the start
+    /// of `contents`.
+    ///
+    /// Arrays and objects may be nested at most [`DEFAULT_MAX_DEPTH`] deep.  To change this, see
+    /// [`Parser`].
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONValueType};
+    /// let (value, value_len) = JSONValue::parse("  [1, 2] [3]").unwrap();
+    /// assert_eq!(value.value_type, JSONValueType::Array);
+    /// assert_eq!(value_len, 8);
+    /// ```
+    pub fn parse(contents: &'a str) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        Parser::new().parse(contents)
+    }
+
+    /// Parse a single value from the start of `contents`, consuming the whitespace after it too
+    ///
+    /// Like [`JSONValue::parse`], but the number of bytes consumed includes any whitespace
+    /// following the value, so it lands on the next token or the end of `contents`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let contents = "42   [1]";
+    /// let (_, value_len) = JSONValue::parse_consuming_trailing_ws(contents).unwrap();
+    /// assert_eq!(&contents[value_len..], "[1]");
+    /// ```
+    pub fn parse_consuming_trailing_ws(
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        Parser::new().parse_consuming_trailing_ws(contents)
+    }
+
+    /// Checks the value at the start of `contents`, returning only the number of bytes consumed
+    ///
+    /// This is the length [`JSONValue::parse`] reports, including leading whitespace, for use by
+    /// code which just needs to step past a value.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let contents = r#"{"skipped": [1, 2]}, "next""#;
+    /// let skipped = JSONValue::skip_value(contents).unwrap();
+    /// assert_eq!(&contents[skipped..], r#", "next""#);
+    /// ```
+    pub fn skip_value(contents: &str) -> Result<usize, JSONLocatedError> {
+        Parser::new().skip_value(contents)
+    }
+
+    /// Parse a single value from the start of `contents`, without the number of bytes consumed
+    ///
+    /// Otherwise the same as [`JSONValue::parse`], so any content after the value is ignored.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::parse_value(r#" {"a": 1} "#).unwrap();
+    /// assert_eq!(value.get_key_value("a").unwrap().read_integer(), Ok(1));
+    /// ```
+    pub fn parse_value(contents: &'a str) -> Result<JSONValue<'a>, JSONLocatedError> {
+        Parser::new().parse_value(contents)
+    }
+
+    /// Parse a single value starting `offset` bytes into `contents`
+    ///
+    /// Returns the value along with the offset just after it, so that a sequence of values can be
+    /// parsed by feeding each returned offset into the next call.  Errors report their offset
+    /// relative to the start of `contents`, and an `offset` past the end of `contents` or inside
+    /// a character is an error.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let contents = "[1, 2] {\"a\": 3}";
+    /// let (first, offset) = JSONValue::parse_at(contents, 0).unwrap();
+    /// let (second, offset) = JSONValue::parse_at(contents, offset).unwrap();
+    /// assert_eq!(first.len(), Ok(2));
+    /// assert_eq!(second.get_key_value("a").unwrap().read_integer(), Ok(3));
+    /// assert_eq!(offset, contents.len());
+    /// ```
+    pub fn parse_at(
+        contents: &'a str,
+        offset: usize,
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        Parser::new().parse_at(contents, offset)
+    }
+
+    /// Parse a single value from the start of `bytes`, which must be UTF-8
+    ///
+    /// This is [`JSONValue::parse`] for input that hasn't been checked to be UTF-8 yet, such as
+    /// data read from a network or flash.  The whole of `bytes` is checked, and if it is not UTF-8
+    /// a [`JSONParsingError::InvalidUtf8`] error is returned at the offset of the first invalid
+    /// byte.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let (value, value_len) = JSONValue::parse_bytes(b"[1, 2]").unwrap();
+    /// assert_eq!(value.get_nth_array_item(1).unwrap().read_integer(), Ok(2));
+    /// assert_eq!(value_len, 6);
+    ///
+    /// let error = JSONValue::parse_bytes(b"[\"\xff\"]").unwrap_err();
+    /// assert_eq!(error.kind, JSONParsingError::InvalidUtf8);
+    /// assert_eq!(error.offset, 2);
+    /// ```
+    pub fn parse_bytes(bytes: &'a [u8]) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        Parser::new().parse_bytes(bytes)
+    }
+
+    /// Whether `contents` is a single well-formed JSON value, aside from surrounding whitespace
+    ///
+    /// This is [`JSONValue::parse_exact`] for when only the answer is needed.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::is_valid(" {\"a\": [1, 2]}\n"));
+    /// assert!(!JSONValue::is_valid("[1, 2,]"));
+    /// ```
+    pub fn is_valid(contents: &str) -> bool {
+        Parser::new().is_valid(contents)
+    }
+
+    /// Constructs an iterator over a sequence of values, such as JSON Lines
+    ///
+    /// Each value is parsed as with [`JSONValue::parse`], and the values may be separated by any
+    /// whitespace, including none at all.  Iteration stops after the first error, whose offset is
+    /// relative to the start of `contents`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let lines = "{\"id\": 1}\n{\"id\": 2}\n";
+    /// for (value, id) in JSONValue::parse_stream(lines).zip(1..) {
+    ///     assert_eq!(value.unwrap().get_key_value("id").unwrap().read_integer(), Ok(id));
+    /// }
+    /// ```
+    pub fn parse_stream(contents: &'a str) -> JSONStreamIterator<'a> {
+        Parser::new().parse_stream(contents)
+    }
+
+    /// Parse a single value from the start of `contents`, along with the bytes it occupies
+    ///
+    /// Like [`JSONValue::parse`], but returns the range of `contents` spanned by the value itself,
+    /// excluding any leading whitespace or byte order mark.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let (value, span) = JSONValue::parse_spanned("  [1, 2] , 3").unwrap();
+    /// assert_eq!(span, 2..8);
+    /// assert_eq!(value.len(), Ok(2));
+    /// ```
+    pub fn parse_spanned(
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, core::ops::Range<usize>), JSONLocatedError> {
+        Parser::new().parse_spanned(contents)
+    }
+
+    /// Parse a single value from the start of `contents`, along with the input following it
+    ///
+    /// Like [`JSONValue::parse`], but returns the unconsumed rest of `contents`, including any
+    /// whitespace after the value, rather than its length.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let mut rest = "1 2 3";
+    /// let mut total = 0;
+    /// while !rest.trim().is_empty() {
+    ///     let (value, remainder) = JSONValue::parse_remainder(rest).unwrap();
+    ///     total += value.read_integer().unwrap();
+    ///     rest = remainder;
+    /// }
+    /// assert_eq!(total, 6);
+    /// ```
+    pub fn parse_remainder(
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, &'a str), JSONLocatedError> {
+        Parser::new().parse_remainder(contents)
+    }
+
+    /// Parse `contents` as a complete JSON document, verifying that it is valid JSON
+    ///
+    /// Unlike [`JSONValue::parse`], only whitespace may follow the value.  Anything else results
+    /// in a [`JSONParsingError::UnexpectedToken`] error at the offset of the trailing content.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// assert!(JSONValue::parse_exact("[1, 2]\n").is_ok());
+    /// let error = JSONValue::parse_exact("[1, 2] [3]").unwrap_err();
+    /// assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+    /// assert_eq!(error.offset, 7);
+    /// ```
+    pub fn parse_exact(contents: &'a str) -> Result<JSONValue<'a>, JSONLocatedError> {
+        Parser::new().parse_exact(contents)
+    }
+
+    /// Parse a single value from the start of `contents`
+    ///
+    /// Returns the value along with the number of bytes consumed (including leading whitespace).
+    /// Errors report their offset relative to the start of `contents`.
+    ///
+    /// Rather than recursing into nested arrays and objects, which would leave the stack usage at
+    /// the mercy of the input, the enclosing containers are tracked in a [`NestingStack`].
+    fn parse_with_len(
+        contents: &'a str,
+        parser: Parser,
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        JSONValue::parse_with_stack(contents, parser, &mut [0; MAX_NESTING_DEPTH / 8])
+    }
+
+    /// [`JSONValue::parse_with_len`], with the buffer for the [`NestingStack`] given by the caller
+    fn parse_with_stack(
+        contents: &'a str,
+        parser: Parser,
+        stack: &mut [u8],
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        let (contents, whitespace_trimmed) = trim_start(contents, parser);
+        let error = |kind, offset: usize| JSONLocatedError {
+            kind,
+            offset: whitespace_trimmed + offset,
+        };
+        let mut stack = NestingStack::new(stack);
+        let max_depth = parser.max_depth.min(stack.capacity());
+        // The start of the value currently being scanned
+        let mut position = 0;
+        loop {
+            let rest = &contents[position..];
+            match rest.chars().next() {
+                Some(chr @ ('{' | '[')) => {
+                    if stack.len() >= max_depth {
+                        return Err(error(JSONParsingError::DepthLimitExceeded, position));
                     }
-                    let (new_contents, whitespace) = trim_start(&contents[item_len..]);
-                    contents = new_contents;
-                    value_len += item_len + whitespace;
-                    if contents.is_empty() {
-                        return Err(JSONParsingError::EndOfStream);
-                    } else if contents.starts_with(':') {
-                        value_len += 1;
-                        contents = &contents[1..];
+                    let is_object = chr == '{';
+                    stack.push(is_object);
+                    position += 1 + trim_start(&rest[1..], parser).1;
+                    if contents[position..].starts_with(if is_object { '}' } else { ']' }) {
+                        stack.pop();
+                        position += 1;
                     } else {
-                        return Err(JSONParsingError::UnexpectedToken);
-                    }
-
-                    let (_, item_len) = JSONValue::parse_with_len(contents)?;
-                    let (new_contents, whitespace) = trim_start(&contents[item_len..]);
-                    contents = new_contents;
-                    value_len += item_len + whitespace;
-                    if contents.is_empty() {
-                        return Err(JSONParsingError::EndOfStream);
-                    } else if contents.starts_with(',') {
-                        value_len += 1;
-                        contents = &contents[1..];
-                    } else if !contents.starts_with('}') {
-                        return Err(JSONParsingError::UnexpectedToken);
+                        if is_object {
+                            position = scan_key(contents, position, parser)
+                                .map_err(|e| e.shifted(whitespace_trimmed))?;
+                        }
+                        continue;
                     }
                 }
-                (JSONValueType::Object, value_len)
-            }
-            Some('[') => {
-                let mut value_len = 1;
-                let mut contents = &contents[value_len..];
-                while !contents.is_empty() {
-                    if contents.trim_start().starts_with(']') {
-                        value_len += trim_start(contents).1 + 1;
-                        break;
-                    }
-                    let (_, item_len) = JSONValue::parse_with_len(contents)?;
-                    let (new_contents, whitespace) = trim_start(&contents[item_len..]);
-                    contents = new_contents;
-                    value_len += item_len + whitespace;
-                    if contents.is_empty() {
-                        return Err(JSONParsingError::EndOfStream);
-                    } else if contents.starts_with(',') {
-                        value_len += 1;
-                        contents = &contents[1..];
-                    } else if !contents.starts_with(']') {
-                        return Err(JSONParsingError::UnexpectedToken);
-                    }
+                Some('"' | '\'') if is_string_start(rest, parser) => {
+                    position +=
+                        scan_string(rest).map_err(|e| e.shifted(whitespace_trimmed + position))?
                 }
-                (JSONValueType::Array, value_len)
-            }
-            Some('"') => {
-                let mut value_len = 1;
-                let mut is_escaped = false;
-                for chr in contents[1..].chars() {
-                    value_len += chr.len_utf8();
-                    if chr == '"' && !is_escaped {
-                        break;
-                    } else if chr == '\\' {
-                        is_escaped = !is_escaped;
-                    } else {
-                        is_escaped = false;
-                    }
+                Some(
+                    '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '-' | '+' | '.',
+                ) => {
+                    position += scan_number(rest, parser)
+                        .map_err(|e| e.shifted(whitespace_trimmed + position))?
+                }
+                Some('N' | 'I') if parser.allow_nan_and_infinity => {
+                    position += scan_number(rest, parser)
+                        .map_err(|e| e.shifted(whitespace_trimmed + position))?
                 }
-                (JSONValueType::String, value_len)
+                Some('t') if rest.starts_with("true") => position += 4,
+                Some('f') if rest.starts_with("false") => position += 5,
+                Some('n') if rest.starts_with("null") => position += 4,
+                None if !stack.is_empty() => {
+                    return Err(error(JSONParsingError::EndOfStream, position));
+                }
+                _ => return Err(error(JSONParsingError::UnexpectedToken, position)),
             }
-            Some('0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '-') => {
-                let mut value_len = 0;
-                for chr in contents.chars() {
-                    match chr {
-                        '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '-' | 'e'
-                        | 'E' | '.' => {
-                            value_len += chr.len_utf8();
+
+            // Having scanned a value, close any containers ending here and find the next value
+            while let Some(is_object) = stack.top() {
+                position += trim_start(&contents[position..], parser).1;
+                match contents[position..].chars().next() {
+                    Some(',') => {
+                        position += 1 + trim_start(&contents[position + 1..], parser).1;
+                        let closing = if is_object { '}' } else { ']' };
+                        if parser.allow_trailing_commas && contents[position..].starts_with(closing)
+                        {
+                            continue;
                         }
-                        _ => {
-                            break;
+                        if is_object {
+                            position = scan_key(contents, position, parser)
+                                .map_err(|e| e.shifted(whitespace_trimmed))?;
                         }
+                        break;
                     }
+                    Some('}') if is_object => {
+                        stack.pop();
+                        position += 1;
+                    }
+                    Some(']') if !is_object => {
+                        stack.pop();
+                        position += 1;
+                    }
+                    None => return Err(error(JSONParsingError::EndOfStream, position)),
+                    _ => return Err(error(JSONParsingError::UnexpectedToken, position)),
                 }
-                (JSONValueType::Number, value_len)
-            }
-            Some('t') => {
-                if &contents[..4] != "true" {
-                    return Err(JSONParsingError::UnexpectedToken);
-                }
-                (JSONValueType::Bool, 4)
-            }
-            Some('f') => {
-                if &contents[..5] != "false" {
-                    return Err(JSONParsingError::UnexpectedToken);
-                }
-                (JSONValueType::Bool, 5)
             }
-            Some('n') => {
-                if &contents[..4] != "null" {
-                    return Err(JSONParsingError::UnexpectedToken);
-                }
-                (JSONValueType::Null, 4)
+            if stack.is_empty() {
+                break;
             }
-            _ => {
-                return Err(JSONParsingError::UnexpectedToken);
+        }
+        if parser.deny_duplicate_keys {
+            if let Some(offset) = parser::find_duplicate_key(&contents[..position], parser) {
+                return Err(error(JSONParsingError::DuplicateKey, offset));
             }
-        };
+        }
         Ok((
             JSONValue {
-                contents: &contents[..value_len],
-                value_type,
+                contents: &contents[..position],
+                value_type: JSONValue::peek_value_type(contents, parser),
+                parser,
             },
-            whitespace_trimmed + value_len,
+            whitespace_trimmed + position,
         ))
     }
 
@@ -259,13 +758,55 @@ impl<'a> JSONValue<'a> {
     ///
     /// let value = JSONValue::load("5pi");
     /// assert_eq!(value.read_integer(), Err(JSONParsingError::CannotParseInteger));
+    ///
+    /// let value = JSONValue::load("99999999999999999999");
+    /// assert_eq!(value.read_integer(), Err(JSONParsingError::NumberOverflow));
     /// ```
     pub fn read_integer(&self) -> Result<isize, JSONParsingError> {
-        if self.value_type != JSONValueType::Number {
-            return Err(JSONParsingError::CannotParseInteger);
-        }
-        let contents = self.contents.trim_end();
-        str::parse(contents).map_err(|_| JSONParsingError::CannotParseInteger)
+        let contents = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseInteger)?;
+        str::parse(contents).map_err(integer_error)
+    }
+
+    /// Reads the [`JSONValue`] as a 64-bit signed integer
+    ///
+    /// If the type is not a [`JSONValueType::Number`], returns an `Err`.  Unlike
+    /// [`JSONValue::read_integer`], the range doesn't depend on the target, so this is the
+    /// recommended way to read large values such as IDs on 32-bit targets.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("-9000000000");
+    /// assert_eq!(value.read_i64(), Ok(-9_000_000_000));
+    /// ```
+    pub fn read_i64(&self) -> Result<i64, JSONParsingError> {
+        let contents = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseInteger)?;
+        str::parse(contents).map_err(integer_error)
+    }
+
+    /// Reads the [`JSONValue`] as a 64-bit unsigned integer
+    ///
+    /// If the type is not a [`JSONValueType::Number`] or the number is negative, returns an
+    /// `Err`.  See [`JSONValue::read_i64`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load("18000000000000000000");
+    /// assert_eq!(value.read_u64(), Ok(18_000_000_000_000_000_000));
+    ///
+    /// let value = JSONValue::load("-1");
+    /// assert_eq!(value.read_u64(), Err(JSONParsingError::CannotParseInteger));
+    /// ```
+    pub fn read_u64(&self) -> Result<u64, JSONParsingError> {
+        let contents = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseInteger)?;
+        str::parse(contents).map_err(integer_error)
     }
 
     /// Reads the [`JSONValue`] as a float
@@ -282,44 +823,597 @@ impl<'a> JSONValue<'a> {
     /// assert_eq!(value.read_float(), Err(JSONParsingError::CannotParseFloat));
     /// ```
     pub fn read_float(&self) -> Result<f32, JSONParsingError> {
+        let contents = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseFloat)?;
+        str::parse(contents).map_err(|_| JSONParsingError::CannotParseFloat)
+    }
+
+    /// Reads the [`JSONValue`] as a double-precision float
+    ///
+    /// If the type is not a [`JSONValueType::Number`], returns an `Err`.  Prefer this over
+    /// [`JSONValue::read_float`] when precision matters.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load("0.1");
+    /// assert_eq!(value.read_f64(), Ok(0.1));
+    ///
+    /// let value = JSONValue::load("\"0.1\"");
+    /// assert_eq!(value.read_f64(), Err(JSONParsingError::CannotParseFloat));
+    /// ```
+    pub fn read_f64(&self) -> Result<f64, JSONParsingError> {
+        let contents = self
+            .number_text()
+            .ok_or(JSONParsingError::CannotParseFloat)?;
+        str::parse(contents).map_err(|_| JSONParsingError::CannotParseFloat)
+    }
+
+    /// The text of this number, if it follows the JSON number grammar
+    fn number_text(&self) -> Option<&'a str> {
         if self.value_type != JSONValueType::Number {
-            return Err(JSONParsingError::CannotParseFloat);
+            return None;
         }
         let contents = self.contents.trim_end();
-        str::parse(contents).map_err(|_| JSONParsingError::CannotParseFloat)
+        if scan_number(contents, self.parser) == Ok(contents.len()) {
+            Some(contents)
+        } else {
+            None
+        }
     }
 
-    /// Read the [`JSONValue`] as a string
+    /// Returns `true` if this value is a number without a fraction or exponent
     ///
-    /// This returns an unescaped string (actually a slice into the underlying bytes). If you need
-    /// escape sequences to be handled, use [`JSONValue::iter_string`].
+    /// This can be used to choose between the integer and float accessors.  Returns `false` for
+    /// values that are not numbers.
     ///
-    /// ## Example
+    /// ### Example
     /// ```
     /// # use microjson::JSONValue;
-    /// let value = JSONValue::load("\"this is a string\"");
-    /// assert_eq!(value.read_string(), Ok("this is a string"));
+    /// assert!(JSONValue::load("42").is_integer());
+    /// assert!(!JSONValue::load("3.14").is_integer());
     /// ```
-    pub fn read_string(&self) -> Result<&'a str, JSONParsingError> {
-        let (_, length) = JSONValue::parse_with_len(self.contents)?;
-        if self.value_type != JSONValueType::String {
-            return Err(JSONParsingError::CannotParseString);
-        }
-        Ok(&self.contents[1..length - 1])
+    pub fn is_integer(&self) -> bool {
+        self.number_text().is_some_and(|text| {
+            text.trim_start_matches(['-', '+'])
+                .bytes()
+                .all(|byte| byte.is_ascii_digit())
+        })
     }
 
-    /// Constructs an iterator over this array value
+    /// Reads the [`JSONValue`] as a boolean
     ///
-    /// If the value is not an [`JSONValueType::Array`], returns an error.
+    /// If the type is not a [`JSONValueType::Bool`], returns an `Err`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load("true");
+    /// assert_eq!(value.read_bool(), Ok(true));
+    ///
+    /// let value = JSONValue::load("12");
+    /// assert_eq!(value.read_bool(), Err(JSONParsingError::CannotParseBool));
+    /// ```
+    pub fn read_bool(&self) -> Result<bool, JSONParsingError> {
+        if self.value_type != JSONValueType::Bool {
+            return Err(JSONParsingError::CannotParseBool);
+        }
+        match self.contents.trim_end() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(JSONParsingError::CannotParseBool),
+        }
+    }
+
+    /// Reads the [`JSONValue`] as a boolean, also accepting the ways some encoders write them
+    ///
+    /// As well as a [`JSONValueType::Bool`], accepts a number equal to `0` or `1`, and a string
+    /// which is `"true"` or `"false"` ignoring ASCII case.  Anything else results in
+    /// `Err(JSONParsingError::CannotParseBool)`.  Use [`JSONValue::read_bool`] to accept only
+    /// JSON booleans.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// assert_eq!(JSONValue::load("1").read_bool_lenient(), Ok(true));
+    /// assert_eq!(JSONValue::load(r#""False""#).read_bool_lenient(), Ok(false));
+    /// assert_eq!(JSONValue::load("2").read_bool_lenient(), Err(JSONParsingError::CannotParseBool));
+    /// ```
+    pub fn read_bool_lenient(&self) -> Result<bool, JSONParsingError> {
+        match self.value_type {
+            JSONValueType::Bool => self.read_bool(),
+            JSONValueType::Number => {
+                for (text, value) in [("0", false), ("1", true)] {
+                    if self.number_cmp(&JSONValue::load(text)) == Ok(core::cmp::Ordering::Equal) {
+                        return Ok(value);
+                    }
+                }
+                Err(JSONParsingError::CannotParseBool)
+            }
+            JSONValueType::String => {
+                self.read_string_raw()?;
+                for (text, value) in [("true", true), ("false", false)] {
+                    let chars = self.iter_string()?;
+                    if chars
+                        .map(|chr| chr.map(|chr| chr.to_ascii_lowercase()))
+                        .eq(text.chars().map(Ok))
+                    {
+                        return Ok(value);
+                    }
+                }
+                Err(JSONParsingError::CannotParseBool)
+            }
+            _ => Err(JSONParsingError::CannotParseBool),
+        }
+    }
+
+    /// The JSON text of this value, without any surrounding whitespace
+    ///
+    /// This is exactly the slice of the input which the value spans, so it can be embedded in
+    /// another document as it is.  If the value is malformed, the rest of the input is returned.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"a": [1, 2], "b": null}"#);
+    /// assert_eq!(value.get_key_value("a").unwrap().raw(), "[1, 2]");
+    /// ```
+    pub fn raw(&self) -> &'a str {
+        match JSONValue::parse_with_len(self.contents, self.parser) {
+            Ok((_, value_len)) => &self.contents[..value_len],
+            Err(_) => self.contents.trim_end(),
+        }
+    }
+
+    /// A name for this value's type, for use in messages
+    ///
+    /// One of `"string"`, `"number"`, `"object"`, `"array"`, `"bool"` or `"null"`, or `"invalid"`
+    /// for a [`JSONValueType::Error`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert_eq!(JSONValue::load("[1, 2]").type_name(), "array");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        match self.value_type {
+            JSONValueType::String => "string",
+            JSONValueType::Number => "number",
+            JSONValueType::Object => "object",
+            JSONValueType::Array => "array",
+            JSONValueType::Bool => "bool",
+            JSONValueType::Null => "null",
+            JSONValueType::Error => "invalid",
+        }
+    }
+
+    /// Returns `true` if this value is a string
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("\"a\"").is_string());
+    /// assert!(!JSONValue::load("a").is_string());
+    /// ```
+    pub fn is_string(&self) -> bool {
+        self.value_type == JSONValueType::String
+    }
+
+    /// Returns `true` if this value is a number
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("1.5").is_number());
+    /// assert!(!JSONValue::load("\"1.5\"").is_number());
+    /// ```
+    pub fn is_number(&self) -> bool {
+        self.value_type == JSONValueType::Number
+    }
+
+    /// Returns `true` if this value is an object
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("{}").is_object());
+    /// assert!(!JSONValue::load("[]").is_object());
+    /// ```
+    pub fn is_object(&self) -> bool {
+        self.value_type == JSONValueType::Object
+    }
+
+    /// Returns `true` if this value is an array
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("[]").is_array());
+    /// assert!(!JSONValue::load("{}").is_array());
+    /// ```
+    pub fn is_array(&self) -> bool {
+        self.value_type == JSONValueType::Array
+    }
+
+    /// Returns `true` if this value is a bool
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("true").is_bool());
+    /// assert!(!JSONValue::load("null").is_bool());
+    /// ```
+    pub fn is_bool(&self) -> bool {
+        self.value_type == JSONValueType::Bool
+    }
+
+    /// Returns `true` if this value is a JSON `null`
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert!(JSONValue::load("null").is_null());
+    /// assert!(!JSONValue::load("\"null\"").is_null());
+    /// ```
+    pub fn is_null(&self) -> bool {
+        self.value_type == JSONValueType::Null
+    }
+
+    /// Converts a `null` value into `None`, and any other value into `Some`
+    ///
+    /// This is useful for optional fields, which are often either absent or `null`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// # fn main() -> Result<(), JSONParsingError> {
+    /// let value = JSONValue::load(r#"{"first_name": "Ada", "middle_name": null}"#);
+    /// let middle_name = value
+    ///     .get_key_value("middle_name")?
+    ///     .as_option()
+    ///     .map(|v| v.read_string_raw())
+    ///     .transpose()?;
+    /// assert_eq!(middle_name, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_option(self) -> Option<JSONValue<'a>> {
+        if self.is_null() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Read the [`JSONValue`] as a string, decoding its escape sequences into `buf`
+    ///
+    /// Returns the decoded string, borrowed from `buf`.  If the value is not a valid
+    /// [`JSONValueType::String`], or has a malformed escape sequence, returns an error.  If the
+    /// decoded string doesn't fit, returns `Err(JSONParsingError::BufferTooSmall)`; use
+    /// [`JSONValue::string_decoded_len`] to size the buffer beforehand.  To borrow the string from
+    /// the input without decoding it, use [`JSONValue::read_string_raw`].
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""two\nlines""#);
+    /// let mut buf = [0; 16];
+    /// assert_eq!(value.read_string(&mut buf), Ok("two\nlines"));
+    /// ```
+    pub fn read_string<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, JSONParsingError> {
+        let len = self.unescape_into(buf)?;
+        str::from_utf8(&buf[..len]).map_err(|_| JSONParsingError::InvalidUtf8)
+    }
+
+    /// Read the [`JSONValue`] as a string, leaving any escape sequences as they are
+    ///
+    /// This returns exactly what is between the quotes in the payload, without any processing.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""two\nlines""#);
+    /// assert_eq!(value.read_string_raw(), Ok(r"two\nlines"));
+    /// ```
+    pub fn read_string_raw(&self) -> Result<&'a str, JSONParsingError> {
+        let (_, length) = JSONValue::parse_with_len(self.contents, self.parser)?;
+        if self.value_type != JSONValueType::String {
+            return Err(JSONParsingError::CannotParseString);
+        }
+        Ok(&self.contents[1..length - 1])
+    }
+
+    /// Reads the [`JSONValue`] as a string of exactly one character, after decoding escapes
+    ///
+    /// If the value is not a valid [`JSONValueType::String`], or has a malformed escape sequence,
+    /// returns an error.  If the decoded string is empty or has more than one character, returns
+    /// `Err(JSONParsingError::CannotParseString)`.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// assert_eq!(JSONValue::load(r#""N""#).read_char(), Ok('N'));
+    /// assert_eq!(JSONValue::load(r#""\u0041""#).read_char(), Ok('A'));
+    /// assert_eq!(JSONValue::load(r#""NE""#).read_char(), Err(JSONParsingError::CannotParseString));
+    /// ```
+    pub fn read_char(&self) -> Result<char, JSONParsingError> {
+        self.read_string_raw()?;
+        let mut chars = self.iter_string()?;
+        match (chars.next(), chars.next()) {
+            (Some(chr), None) => chr,
+            (Some(Err(e)), _) | (_, Some(Err(e))) => Err(e),
+            _ => Err(JSONParsingError::CannotParseString),
+        }
+    }
+
+    /// Whether every character of this string is ASCII once escape sequences are decoded
+    ///
+    /// If the value is not a valid [`JSONValueType::String`], or has a malformed escape sequence,
+    /// returns an error.  Strings without escape sequences are checked without decoding them.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert_eq!(JSONValue::load(r#""plain\ttext""#).string_is_ascii(), Ok(true));
+    /// assert_eq!(JSONValue::load(r#""caf\u00e9""#).string_is_ascii(), Ok(false));
+    /// ```
+    pub fn string_is_ascii(&self) -> Result<bool, JSONParsingError> {
+        let contents = self.read_string_raw()?;
+        if !contents.contains('\\') {
+            return Ok(contents.is_ascii());
+        }
+        self.iter_string()?.try_fold(true, |is_ascii, chr| {
+            chr.map(|chr| is_ascii && chr.is_ascii())
+        })
+    }
+
+    /// The length in bytes of this string once escape sequences are decoded
+    ///
+    /// If the value is not a valid [`JSONValueType::String`], or has a malformed escape sequence,
+    /// returns an error.  This is the size of buffer needed to hold the decoded string, and is
+    /// found without decoding it anywhere.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""caf\u00e9\n""#);
+    /// assert_eq!(value.string_decoded_len(), Ok("café\n".len()));
+    /// ```
+    pub fn string_decoded_len(&self) -> Result<usize, JSONParsingError> {
+        self.read_string_raw()?;
+        self.iter_string()?.map(|chr| chr.map(char::len_utf8)).sum()
+    }
+
+    /// The number of UTF-16 code units in this string once escape sequences are decoded
+    ///
+    /// This is the length the string has in languages such as JavaScript and Java, where
+    /// characters outside the Basic Multilingual Plane take two code units.  If the value is not a
+    /// valid [`JSONValueType::String`], or has a malformed escape sequence, returns an error.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""café \ud83d\ude00""#);
+    /// assert_eq!(value.string_utf16_len(), Ok(7));
+    /// ```
+    pub fn string_utf16_len(&self) -> Result<usize, JSONParsingError> {
+        self.read_string_raw()?;
+        self.iter_string()?
+            .map(|chr| chr.map(char::len_utf16))
+            .sum()
+    }
+
+    /// Decodes this string's escape sequences into `buf`, returning the number of bytes written
+    ///
+    /// If the value is not a valid [`JSONValueType::String`] or has a malformed escape sequence,
+    /// returns an error.  If the decoded string doesn't fit, returns
+    /// `Err(JSONParsingError::BufferTooSmall)`; whatever was written before that is still valid
+    /// UTF-8, as only whole characters are written.  Use [`JSONValue::string_decoded_len`] to
+    /// size the buffer beforehand.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""caf\u00e9\n""#);
+    /// let mut buf = [0; 16];
+    /// let len = value.unescape_into(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], "café\n".as_bytes());
+    /// ```
+    pub fn unescape_into(&self, buf: &mut [u8]) -> Result<usize, JSONParsingError> {
+        self.read_string_raw()?;
+        decode_into(self.iter_string()?, buf)
+    }
+
+    /// Reads the [`JSONValue`] as a string, decoding all escape sequences into a new `String`
+    ///
+    /// If the value is not a valid [`JSONValueType::String`], or has a malformed escape sequence,
+    /// returns an error.  Only available with the `alloc` feature.
+    ///
+    /// ## Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#""caf\u00e9 \ud83d\ude00""#);
+    /// assert_eq!(value.read_string_owned().unwrap(), "café 😀");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn read_string_owned(&self) -> Result<alloc::string::String, JSONParsingError> {
+        self.read_string_raw()?;
+        self.iter_string()?.collect()
+    }
+
+    /// Constructs an iterator over this array value
+    ///
+    /// If the value is not an [`JSONValueType::Array`], returns an error.
+    ///
+    /// The array is walked once, so this is the preferred way to visit every element.  Iteration
+    /// stops early if the array is malformed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load("[1, 2, 3]");
+    /// let items: Result<Vec<isize>, JSONParsingError> =
+    ///     value.iter_array().unwrap().map(|v| v.read_integer()).collect();
+    /// assert_eq!(items, Ok(vec![1, 2, 3]));
+    /// ```
     pub fn iter_array(&self) -> Result<JSONArrayIterator<'a>, JSONParsingError> {
         if self.value_type != JSONValueType::Array {
             return Err(JSONParsingError::CannotParseArray);
         }
         Ok(JSONArrayIterator {
             contents: &self.contents[1..],
+            parser: self.parser,
+            expect_end: false,
+            done: false,
         })
     }
 
+    /// Constructs an iterator over the elements of this array along with their indices
+    ///
+    /// If the value is not an [`JSONValueType::Array`], returns an error.  This is
+    /// [`JSONValue::iter_array`] followed by [`Iterator::enumerate`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[2, 4, -1]");
+    /// let invalid = value
+    ///     .array_iter_indexed()
+    ///     .unwrap()
+    ///     .find(|(_, item)| item.read_integer().map_or(true, |n| n < 0));
+    /// assert_eq!(invalid.map(|(index, _)| index), Some(2));
+    /// ```
+    pub fn array_iter_indexed(
+        &self,
+    ) -> Result<impl Iterator<Item = (usize, JSONValue<'a>)>, JSONParsingError> {
+        Ok(self.iter_array()?.enumerate())
+    }
+
+    /// Lazily applies `f` to each element of this array
+    ///
+    /// If the value is not an [`JSONValueType::Array`], the iterator yields that error once and
+    /// then ends.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[1, 2, 3]");
+    /// let items: Result<Vec<i64>, _> = value.map_array(|item| item.read_i64()).collect();
+    /// assert_eq!(items, Ok(vec![1, 2, 3]));
+    /// ```
+    pub fn map_array<T, F>(&self, f: F) -> impl Iterator<Item = Result<T, JSONParsingError>> + 'a
+    where
+        T: 'a,
+        F: FnMut(JSONValue<'a>) -> Result<T, JSONParsingError> + 'a,
+    {
+        let (items, error) = match self.iter_array() {
+            Ok(items) => (Some(items), None),
+            Err(error) => (None, Some(Err(error))),
+        };
+        error.into_iter().chain(items.into_iter().flatten().map(f))
+    }
+
+    /// Folds every element of this array into an accumulator with `f`, starting from `init`
+    ///
+    /// If the value is not a valid [`JSONValueType::Array`], or `f` returns an error, returns that
+    /// error.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[1, 2, 3]");
+    /// let sum = value.fold_array(0, |sum, item| Ok(sum + item.read_i64()?));
+    /// assert_eq!(sum, Ok(6));
+    /// ```
+    pub fn fold_array<B, F>(&self, init: B, f: F) -> Result<B, JSONParsingError>
+    where
+        F: FnMut(B, JSONValue<'a>) -> Result<B, JSONParsingError>,
+    {
+        let mut items = self.iter_array()?;
+        self.verify()?;
+        items.try_fold(init, f)
+    }
+
+    /// Reads the elements of this array of numbers into `out`, returning how many were written
+    ///
+    /// If the array is longer than `out`, only the first `out.len()` elements are read.  If the
+    /// value is not a valid [`JSONValueType::Array`], or an element which would be written is not a
+    /// number, returns an error.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[0.1, 0.2, 0.3]");
+    /// let mut samples = [0.0; 8];
+    /// let len = value.read_number_array_into(&mut samples).unwrap();
+    /// assert_eq!(samples[..len], [0.1, 0.2, 0.3]);
+    /// ```
+    pub fn read_number_array_into(&self, out: &mut [f32]) -> Result<usize, JSONParsingError> {
+        let items = self.iter_array()?;
+        self.verify()?;
+        let mut written = 0;
+        for (target, item) in out.iter_mut().zip(items) {
+            *target = item.read_float()?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Collects the elements of this array into a `Vec`
+    ///
+    /// If the value is not a valid [`JSONValueType::Array`], returns an error rather than the
+    /// elements before the problem.  Only available with the `alloc` feature.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"[1, "two", [3]]"#);
+    /// let items = value.to_array_vec().unwrap();
+    /// assert_eq!(items.len(), 3);
+    /// assert_eq!(items[1].read_string(&mut [0; 64]), Ok("two"));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_array_vec(&self) -> Result<alloc::vec::Vec<JSONValue<'a>>, JSONParsingError> {
+        let items = self.iter_array()?;
+        self.verify()?;
+        Ok(items.collect())
+    }
+
+    /// Collects the members of this object into a `Vec`, sorted by key
+    ///
+    /// Each key is decoded into a `String`, and keys are compared in the byte order of their
+    /// UTF-8 encoding.  Members with equal keys are kept in document order.  If the value is not a
+    /// valid [`JSONValueType::Object`], or a key has a malformed escape sequence, returns an
+    /// error.  Only available with the `alloc` feature.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"b": 1, "\u0061": 2}"#);
+    /// let entries = value.entries_sorted().unwrap();
+    /// assert_eq!(entries[0].0, "a");
+    /// assert_eq!(entries[1].1.read_integer(), Ok(1));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn entries_sorted(
+        &self,
+    ) -> Result<alloc::vec::Vec<(alloc::string::String, JSONValue<'a>)>, JSONParsingError> {
+        let members = self.iter_object()?;
+        self.verify()?;
+        let mut entries = members
+            .map(|member| {
+                let (key, value) = member?;
+                Ok((
+                    decode_key(key).collect::<Result<alloc::string::String, _>>()?,
+                    value,
+                ))
+            })
+            .collect::<Result<alloc::vec::Vec<_>, JSONParsingError>>()?;
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(entries)
+    }
+
     /// Constructs an iterator over this string
     ///
     /// If the value is not an [`JSONValueType::String`], returns an error.
@@ -340,6 +1434,7 @@ impl<'a> JSONValue<'a> {
         }
         Ok(EscapedStringIterator {
             contents: self.contents[1..].chars(),
+            quote: self.contents.chars().next().unwrap_or('"'),
             done: false,
         })
     }
@@ -347,400 +1442,2874 @@ impl<'a> JSONValue<'a> {
     /// Constructs an iterator over this object
     ///
     /// If the value is not an [`JSONValueType::Object`], returns an error.
+    ///
+    /// Members are yielded as `(key, value)` pairs in document order, in a single pass over the
+    /// object.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"a": 1, "b": 2}"#);
+    /// for (n, item) in value.iter_object().unwrap().enumerate() {
+    ///     let (key, value) = item.unwrap();
+    ///     assert_eq!(key, ["a", "b"][n]);
+    ///     assert_eq!(value.read_integer(), Ok(n as isize + 1));
+    /// }
+    /// ```
     pub fn iter_object(&self) -> Result<JSONObjectIterator<'a>, JSONParsingError> {
         if self.value_type != JSONValueType::Object {
             return Err(JSONParsingError::CannotParseObject);
         }
         Ok(JSONObjectIterator {
             contents: &self.contents[1..],
+            parser: self.parser,
+            expect_end: false,
+            done: false,
         })
     }
 
-    /// Searches this object for a key and returns it's value
+    /// Constructs an iterator over the keys of this object
     ///
-    /// Like the function [`Iterator::nth`], this searches linearly through all the keys in the
-    /// object to find the desired one. If parsing the entire object in an arbitrary order, then,
-    /// prefer using [`JSONValue::iter_object`].
+    /// If the value is not an [`JSONValueType::Object`], returns an error.
     ///
-    /// Will return `Err(JSONParsingError::KeyNotFound)` if the key is not present.
-    pub fn get_key_value(&self, key: &str) -> Result<JSONValue, JSONParsingError> {
-        self.iter_object()?
-            .find(|item| matches!(item, Ok((k, _)) if k == &key))
-            .map(|item| item.unwrap().1)
-            .ok_or(JSONParsingError::KeyNotFound)
+    /// Values are skipped over, so keys of nested objects are not yielded.  As with
+    /// [`JSONValue::iter_object`], the keys are not unescaped.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"outer": {"inner": 1}, "x": 2}"#);
+    /// let keys: Result<Vec<&str>, _> = value.iter_keys().unwrap().collect();
+    /// assert_eq!(keys.unwrap(), ["outer", "x"]);
+    /// ```
+    pub fn iter_keys(&self) -> Result<JSONKeyIterator<'a>, JSONParsingError> {
+        Ok(JSONKeyIterator {
+            members: self.iter_object()?,
+        })
     }
-}
-
-/// An iterator through a JSON object
-///
-/// Usually constructed with [`JSONValue::iter_object`].
-///
-/// The iterator items are `Result<(key, value), JSONParsingError>`, but the key is not escaped
-pub struct JSONObjectIterator<'a> {
-    contents: &'a str,
-}
+
+    /// The number of elements in this array, or members in this object
+    ///
+    /// If the value is neither an [`JSONValueType::Array`] nor an [`JSONValueType::Object`],
+    /// returns an error.  Nested containers count as a single element.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// assert_eq!(JSONValue::load("[1, [2, 3], {}]").len(), Ok(3));
+    /// assert_eq!(JSONValue::load(r#"{"a": [1, 2]}"#).len(), Ok(1));
+    /// assert!(JSONValue::load("42").len().is_err());
+    /// ```
+    pub fn len(&self) -> Result<usize, JSONParsingError> {
+        match self.value_type {
+            JSONValueType::Array => self.array_len(),
+            JSONValueType::Object => self.object_len(),
+            _ => Err(JSONParsingError::NotAContainer),
+        }
+    }
+
+    /// The number of elements in this array
+    ///
+    /// If the value is not an [`JSONValueType::Array`], or an element can't be parsed, returns an
+    /// error.  See also [`JSONValue::len`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// assert_eq!(JSONValue::load("[1, [2, 3]]").array_len(), Ok(2));
+    /// assert_eq!(JSONValue::load("{}").array_len(), Err(JSONParsingError::CannotParseArray));
+    /// ```
+    pub fn array_len(&self) -> Result<usize, JSONParsingError> {
+        let mut items = self.iter_array()?;
+        let mut count = 0;
+        while let Some(item) = items.next_element() {
+            item?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// The number of members in this object
+    ///
+    /// If the value is not an [`JSONValueType::Object`], returns an error.  See also
+    /// [`JSONValue::len`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// assert_eq!(JSONValue::load(r#"{"a": [1, 2]}"#).object_len(), Ok(1));
+    /// assert_eq!(JSONValue::load("[]").object_len(), Err(JSONParsingError::CannotParseObject));
+    /// ```
+    pub fn object_len(&self) -> Result<usize, JSONParsingError> {
+        self.iter_object()?
+            .try_fold(0, |count, item| item.map(|_| count + 1))
+    }
+
+    /// Whether this array or object has no elements
+    ///
+    /// See [`JSONValue::len`].
+    pub fn is_empty(&self) -> Result<bool, JSONParsingError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Returns the `n`th element of this array
+    ///
+    /// Like the function [`Iterator::nth`], this searches linearly through the array.  If visiting
+    /// every element, prefer using [`JSONValue::iter_array`].
+    ///
+    /// Will return `Err(JSONParsingError::IndexOutOfBounds)` if the array has `n` or fewer
+    /// elements, or the error if one of the first `n + 1` elements can't be parsed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load("[1, 2, 3]");
+    /// assert_eq!(value.get_nth_array_item(2).unwrap().read_integer(), Ok(3));
+    /// assert_eq!(value.get_nth_array_item(3).err(), Some(JSONParsingError::IndexOutOfBounds));
+    /// ```
+    pub fn get_nth_array_item(&self, n: usize) -> Result<JSONValue<'a>, JSONParsingError> {
+        let mut items = self.iter_array()?;
+        for _ in 0..n {
+            items
+                .next_element()
+                .ok_or(JSONParsingError::IndexOutOfBounds)??;
+        }
+        items
+            .next_element()
+            .ok_or(JSONParsingError::IndexOutOfBounds)?
+    }
+
+    /// Returns the `n`th member of this object as a `(key, value)` pair
+    ///
+    /// Members are counted in document order, searching linearly through the object.  As with
+    /// [`JSONValue::iter_object`], the key is not unescaped.
+    ///
+    /// Will return `Err(JSONParsingError::IndexOutOfBounds)` if the object has `n` or fewer
+    /// members.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, JSONParsingError};
+    /// let value = JSONValue::load(r#"{"a": 1, "b": 2}"#);
+    /// let (key, value) = value.nth_entry(1).unwrap();
+    /// assert_eq!((key, value.read_integer()), ("b", Ok(2)));
+    /// ```
+    pub fn nth_entry(&self, n: usize) -> Result<(&'a str, JSONValue<'a>), JSONParsingError> {
+        self.iter_object()?
+            .nth(n)
+            .ok_or(JSONParsingError::IndexOutOfBounds)?
+    }
+
+    /// The index of the first element of this array satisfying `predicate`
+    ///
+    /// The array is walked once.  Returns `None` if no element matches, or if this value is not an
+    /// array.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[1, 2, 3, 4]");
+    /// assert_eq!(value.find_index(|item| item.read_integer().unwrap() > 2), Some(2));
+    /// ```
+    pub fn find_index<F: Fn(JSONValue<'a>) -> bool>(&self, predicate: F) -> Option<usize> {
+        self.iter_array().ok()?.position(predicate)
+    }
+
+    /// Searches this object for a key and returns it's value
+    ///
+    /// Like the function [`Iterator::nth`], this searches linearly through all the keys in the
+    /// object to find the desired one. If parsing the entire object in an arbitrary order, then,
+    /// prefer using [`JSONValue::iter_object`].
+    ///
+    /// Keys are compared after their escape sequences are decoded, so `"\u0061"` is found by
+    /// looking up `"a"`.
+    ///
+    /// Will return `Err(JSONParsingError::KeyNotFound)` if the key is not present, or the parsing
+    /// error if a malformed member is encountered before the key is found.
+    pub fn get_key_value(&self, key: &str) -> Result<JSONValue<'a>, JSONParsingError> {
+        for item in self.iter_object()? {
+            let (this_key, value) = item?;
+            if decode_key(this_key).eq(key.chars().map(Ok)) {
+                return Ok(value);
+            }
+        }
+        Err(JSONParsingError::KeyNotFound)
+    }
+
+    /// Searches this object for a key, ignoring ASCII case, and returns it's value
+    ///
+    /// This is [`JSONValue::get_key_value`], except that ASCII letters match regardless of case.
+    /// Only ASCII is case folded, so `"É"` and `"é"` are still different keys.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"content-type": "text/plain"}"#);
+    /// let content_type = value.get_key_value_ci("Content-Type").unwrap();
+    /// assert_eq!(content_type.read_string(&mut [0; 64]), Ok("text/plain"));
+    /// ```
+    pub fn get_key_value_ci(&self, key: &str) -> Result<JSONValue<'a>, JSONParsingError> {
+        let lowercase = |chr: char| chr.to_ascii_lowercase();
+        for item in self.iter_object()? {
+            let (this_key, value) = item?;
+            if decode_key(this_key)
+                .map(|chr| chr.map(lowercase))
+                .eq(key.chars().map(lowercase).map(Ok))
+            {
+                return Ok(value);
+            }
+        }
+        Err(JSONParsingError::KeyNotFound)
+    }
+
+    /// Returns the value for `key` in this object, or `None` if there isn't one
+    ///
+    /// This is [`JSONValue::get_key_value`] for when a missing member is expected.  Values that
+    /// are not objects, and malformed objects, also give `None`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"name": "Ada"}"#);
+    /// assert_eq!(value.get("name").unwrap().read_string(&mut [0; 64]), Ok("Ada"));
+    /// assert!(value.get("age").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<JSONValue<'a>> {
+        self.get_key_value(key).ok()
+    }
+
+    /// Returns the `n`th element of this array, or `None` if there isn't one
+    ///
+    /// This is [`JSONValue::get_nth_array_item`] for when a missing element is expected.  Values
+    /// that are not arrays also give `None`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load("[1, 2]");
+    /// assert_eq!(value.get_index(1).unwrap().read_integer(), Ok(2));
+    /// assert!(value.get_index(2).is_none());
+    /// ```
+    pub fn get_index(&self, n: usize) -> Option<JSONValue<'a>> {
+        self.get_nth_array_item(n).ok()
+    }
+
+    /// Iterates over the values of every member of this object with the given key
+    ///
+    /// Objects with duplicate keys are accepted unless the parser denies them, and
+    /// [`JSONValue::get_key_value`] only finds the first such member.  This finds all of them, in
+    /// the order they appear.  Only the members of this object are considered, not those of any
+    /// nested objects.  Iteration stops at a malformed member, and there are no values if this
+    /// value is not an object.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"a": 1, "b": 2, "a": 3}"#);
+    /// let mut values = value.values_for_key("a");
+    /// assert_eq!(values.next().unwrap().read_integer(), Ok(1));
+    /// assert_eq!(values.next().unwrap().read_integer(), Ok(3));
+    /// assert!(values.next().is_none());
+    /// ```
+    pub fn values_for_key<'k>(&self, key: &'k str) -> impl Iterator<Item = JSONValue<'a>> + 'k
+    where
+        'a: 'k,
+    {
+        self.iter_object()
+            .into_iter()
+            .flatten()
+            .map_while(Result::ok)
+            .filter(move |(this_key, _)| decode_key(this_key).eq(key.chars().map(Ok)))
+            .map(|(_, value)| value)
+    }
+
+    /// Whether this object has a member with the given key
+    ///
+    /// Returns `false` if this value is not an object.  Only the members of this object are
+    /// considered, not those of any nested objects.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"a": {"b": 1}}"#);
+    /// assert!(value.contains_key("a"));
+    /// assert!(!value.contains_key("b"));
+    /// ```
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get_key_value(key).is_ok()
+    }
+
+    /// The value of the member with the given key, or the value computed by `f` if there is none
+    ///
+    /// The default is also used if this value is not an object, or is malformed before the key is
+    /// found, in just the cases where [`JSONValue::get_key_value`] would return an error.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let config = JSONValue::load(r#"{"port": 8080}"#);
+    /// let default = || JSONValue::load("30");
+    /// assert_eq!(config.get_or_else("port", default).read_integer(), Ok(8080));
+    /// assert_eq!(config.get_or_else("timeout", default).read_integer(), Ok(30));
+    /// ```
+    pub fn get_or_else<F: FnOnce() -> JSONValue<'a>>(&self, key: &str, f: F) -> JSONValue<'a> {
+        self.get_key_value(key).unwrap_or_else(|_| f())
+    }
+
+    /// The number of members of this object whose key satisfies `predicate`
+    ///
+    /// The object is walked once, and each key is decoded before being passed to `predicate`.
+    /// Keys without escape sequences are passed straight from the input, while the others are
+    /// decoded on the stack, into a buffer of 256 bytes.  An escaped key which decodes to more
+    /// than that returns `Err(JSONParsingError::CannotParseString)`.  If the value is not an
+    /// [`JSONValueType::Object`], or is malformed, returns an error.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"x1": 1, "\u00782": 2, "y": 3}"#);
+    /// assert_eq!(value.count_keys(|key| key.starts_with('x')), Ok(2));
+    /// ```
+    pub fn count_keys<F: Fn(&str) -> bool>(&self, predicate: F) -> Result<usize, JSONParsingError> {
+        let mut buf = [0; 256];
+        let mut count = 0;
+        for key in self.iter_keys()? {
+            let key = key?;
+            let matches = if key.contains('\\') {
+                let len = decode_into(decode_key(key), &mut buf).map_err(|e| match e {
+                    JSONParsingError::BufferTooSmall => JSONParsingError::CannotParseString,
+                    e => e,
+                })?;
+                predicate(str::from_utf8(&buf[..len]).map_err(|_| JSONParsingError::InvalidUtf8)?)
+            } else {
+                predicate(key)
+            };
+            if matches {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Writes the JSON text of the value, exactly as it appears in the input
+///
+/// Whitespace around the value is left out.  Content following a lazily loaded value is too, as
+/// long as the value is well formed.
+///
+/// ### Example
+/// ```
+/// # use microjson::JSONValue;
+/// let value = JSONValue::load(r#"  {"a": [1, 2]}  "#);
+/// assert_eq!(format!("{}", value), r#"{"a": [1, 2]}"#);
+/// ```
+impl core::fmt::Display for JSONValue<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.raw())
+    }
+}
+
+/// An iterator through a JSON object
+///
+/// Usually constructed with [`JSONValue::iter_object`].
+///
+/// The iterator items are `Result<(key, value), JSONParsingError>`, but the key is not escaped
+pub struct JSONObjectIterator<'a> {
+    contents: &'a str,
+    parser: Parser,
+    // Set if the last member was not followed by a comma, so another member would be an error
+    expect_end: bool,
+    done: bool,
+}
 
 impl<'a> Iterator for JSONObjectIterator<'a> {
     type Item = Result<(&'a str, JSONValue<'a>), JSONParsingError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.contents = self.contents.trim_start();
+        if self.done {
+            return None;
+        }
+        self.contents = trim_start(self.contents, self.parser).0;
         if self.contents.is_empty() {
-            None
+            // The input ended before the closing brace
+            self.done = true;
+            Some(Err(JSONParsingError::EndOfStream))
         } else {
-            if !self.contents.starts_with('\"') {
-                self.contents = &self.contents[..0];
+            if self.contents.starts_with('}') {
+                self.done = true;
                 return None;
+            } else if self.expect_end {
+                self.done = true;
+                return Some(Err(JSONParsingError::UnexpectedToken));
             }
-            // We expect this to be a string value for the key
-            match JSONValue::parse_with_len(self.contents) {
-                Ok((_, key_len)) => {
-                    let this_key = &self.contents[1..key_len - 1];
-                    self.contents = &self.contents[key_len..].trim_start()[1..];
-
-                    match JSONValue::parse_with_len(self.contents) {
-                        Ok((this_value, value_len)) => {
-                            self.contents = &self.contents[value_len..].trim_start();
-                            if !self.contents.is_empty() {
-                                self.contents = &self.contents[1..];
-                            }
-                            Some(Ok((this_key, this_value)))
-                        }
-                        Err(e) => {
-                            self.contents = &self.contents[..0];
-                            Some(Err(e))
+            let (this_key, key_len) = match self.scan_key() {
+                Ok(key) => key,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            self.contents = match trim_start(&self.contents[key_len..], self.parser)
+                .0
+                .strip_prefix(':')
+            {
+                Some(rest) => rest,
+                None => {
+                    self.done = true;
+                    return Some(Err(JSONParsingError::UnexpectedToken));
+                }
+            };
+
+            match JSONValue::parse_with_len(self.contents, self.parser) {
+                Ok((this_value, value_len)) => {
+                    let rest = trim_start(&self.contents[value_len..], self.parser).0;
+                    // Without a comma, the next call must find the end of the object
+                    match rest.strip_prefix(',') {
+                        Some(rest) => self.contents = rest,
+                        None => {
+                            self.contents = rest;
+                            self.expect_end = true;
                         }
                     }
+                    Some(Ok((this_key, this_value)))
                 }
                 Err(e) => {
-                    self.contents = &self.contents[..0];
-                    Some(Err(e))
+                    self.done = true;
+                    Some(Err(e.kind))
+                }
+            }
+        }
+    }
+}
+
+impl<'a> JSONObjectIterator<'a> {
+    /// Scans the key at the start of the remaining contents
+    ///
+    /// Returns the key without any quotes, along with its length in the contents.
+    fn scan_key(&self) -> Result<(&'a str, usize), JSONParsingError> {
+        let identifier_len = identifier_len(self.contents);
+        if self.parser.allow_unquoted_keys && identifier_len > 0 {
+            Ok((&self.contents[..identifier_len], identifier_len))
+        } else if is_string_start(self.contents, self.parser) {
+            let (_, key_len) = JSONValue::parse_with_len(self.contents, self.parser)?;
+            Ok((&self.contents[1..key_len - 1], key_len))
+        } else {
+            Err(JSONParsingError::UnexpectedToken)
+        }
+    }
+}
+
+/// An iterator through the keys of a JSON object
+///
+/// Usually constructed with [`JSONValue::iter_keys`].
+pub struct JSONKeyIterator<'a> {
+    members: JSONObjectIterator<'a>,
+}
+
+impl<'a> Iterator for JSONKeyIterator<'a> {
+    type Item = Result<&'a str, JSONParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.members.next().map(|item| item.map(|(key, _)| key))
+    }
+}
+
+/// An iterator through a JSON array value
+///
+/// Usually constructed with [`JSONValue::iter_array`].
+///
+/// Iteration stops at the end of the array, or at the first element which can't be parsed, such
+/// as one that is malformed or nested too deeply.  [`JSONValue::array_len`] and
+/// [`JSONValue::get_nth_array_item`] report the error instead.
+pub struct JSONArrayIterator<'a> {
+    contents: &'a str,
+    parser: Parser,
+    // Set if the last element was not followed by a comma, so another element would be an error
+    expect_end: bool,
+    done: bool,
+}
+
+impl<'a> Iterator for JSONArrayIterator<'a> {
+    type Item = JSONValue<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_element()?.ok()
+    }
+}
+
+impl<'a> JSONArrayIterator<'a> {
+    /// The next element, or an error if there is something other than an element or the end of
+    /// the array
+    fn next_element(&mut self) -> Option<Result<JSONValue<'a>, JSONParsingError>> {
+        if self.done {
+            return None;
+        }
+        let rest = trim_start(self.contents, self.parser).0;
+        if rest.starts_with(']') {
+            self.done = true;
+            return None;
+        } else if rest.is_empty() || self.expect_end {
+            self.done = true;
+            return Some(Err(if rest.is_empty() {
+                JSONParsingError::EndOfStream
+            } else {
+                JSONParsingError::UnexpectedToken
+            }));
+        }
+        match JSONValue::parse_with_len(rest, self.parser) {
+            Ok((value, value_len)) => {
+                let rest = trim_start(&rest[value_len..], self.parser).0;
+                // Without a comma, the next call must find the end of the array
+                match rest.strip_prefix(',') {
+                    Some(rest) => self.contents = rest,
+                    None => {
+                        self.contents = rest;
+                        self.expect_end = true;
+                    }
                 }
+                Some(Ok(value))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e.kind))
+            }
+        }
+    }
+}
+
+/// Iterating from the back rescans the remaining elements to find the last one, so reversing a
+/// whole array takes time quadratic in its length.
+impl<'a> DoubleEndedIterator for JSONArrayIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let remaining = JSONArrayIterator {
+            contents: self.contents,
+            parser: self.parser,
+            expect_end: self.expect_end,
+            done: self.done,
+        };
+        let last = remaining.fold(None, |_, item| Some(item))?;
+        // Cutting the contents off before the last element leaves a trailing comma, which ends
+        // the forward iteration
+        let start = last.contents.as_ptr() as usize - self.contents.as_ptr() as usize;
+        self.contents = &self.contents[..start];
+        Some(last)
+    }
+}
+
+/// An iterator through a sequence of JSON values
+///
+/// Usually constructed with [`JSONValue::parse_stream`].
+pub struct JSONStreamIterator<'a> {
+    contents: &'a str,
+    offset: usize,
+    parser: Parser,
+}
+
+impl<'a> Iterator for JSONStreamIterator<'a> {
+    type Item = Result<JSONValue<'a>, JSONLocatedError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if trim_start(self.contents, self.parser).0.is_empty() {
+            return None;
+        }
+        match JSONValue::parse_with_len(self.contents, self.parser) {
+            Ok((value, value_len)) => {
+                self.contents = &self.contents[value_len..];
+                self.offset += value_len;
+                Some(Ok(value))
+            }
+            Err(error) => {
+                self.contents = &self.contents[..0];
+                Some(Err(error.shifted(self.offset)))
+            }
+        }
+    }
+}
+
+/// Iterator over a JSON-escaped string
+///
+/// See [`JSONValue::iter_string`] for further documentation.
+pub struct EscapedStringIterator<'a> {
+    contents: core::str::Chars<'a>,
+    // The quote which opened the string, and so ends it
+    quote: char,
+    done: bool,
+}
+
+impl<'a> EscapedStringIterator<'a> {
+    /// Reads the four hex digits of a `\u` escape
+    fn read_code_unit(&mut self) -> Result<u32, JSONParsingError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = match self.contents.next() {
+                None => return Err(JSONParsingError::TooShortEscapeSequence),
+                Some(x) if x == self.quote => return Err(JSONParsingError::TooShortEscapeSequence),
+                Some(x) => x
+                    .to_digit(16)
+                    .ok_or(JSONParsingError::InvalidUnicodeEscapeSequence)?,
+            };
+            code = (code << 4) | digit;
+        }
+        Ok(code)
+    }
+
+    /// Reads the rest of a `\u` escape, along with the second half of a surrogate pair
+    fn read_unicode_escape(&mut self) -> Result<char, JSONParsingError> {
+        let mut code = self.read_code_unit()?;
+        // Characters outside the BMP are escaped as a pair of surrogates
+        if (0xd800..0xdc00).contains(&code) {
+            if self.contents.next() != Some('\\') || self.contents.next() != Some('u') {
+                return Err(JSONParsingError::InvalidUnicodeEscapeSequence);
+            }
+            let low = self.read_code_unit()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(JSONParsingError::InvalidUnicodeEscapeSequence);
             }
+            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+        }
+        char::from_u32(code).ok_or(JSONParsingError::InvalidUnicodeEscapeSequence)
+    }
+}
+
+impl<'a> Iterator for EscapedStringIterator<'a> {
+    type Item = Result<char, JSONParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            None
+        } else {
+            let chr = self.contents.next();
+            match chr {
+                Some('\\') => {
+                    let chr = self.contents.next();
+                    match chr {
+                        Some('"' | '\\' | '/') => chr.map(Ok),
+                        Some('\'') if self.quote != '"' => chr.map(Ok),
+                        Some('b') => Some(Ok('\x08')),
+                        Some('f') => Some(Ok('\x0c')),
+                        Some('n') => Some(Ok('\n')),
+                        Some('t') => Some(Ok('\t')),
+                        Some('r') => Some(Ok('\r')),
+                        Some('u') => match self.read_unicode_escape() {
+                            Ok(chr) => Some(Ok(chr)),
+                            Err(e) => {
+                                self.done = true;
+                                Some(Err(e))
+                            }
+                        },
+                        Some(x) => {
+                            self.done = true;
+                            Some(Err(JSONParsingError::InvalidEscapeSequence(x)))
+                        }
+                        None => {
+                            self.done = true;
+                            Some(Err(JSONParsingError::EndOfStream))
+                        }
+                    }
+                }
+                Some(x) if x == self.quote => {
+                    self.done = true;
+                    None
+                }
+                None => {
+                    self.done = true;
+                    Some(Err(JSONParsingError::EndOfStream))
+                }
+                _ => chr.map(Ok),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    extern crate std;
+
+    #[test]
+    fn integer() {
+        let (value, value_len) = JSONValue::parse("42").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Number);
+        assert_eq!(value_len, 2);
+        assert_eq!(value.read_integer(), Ok(42));
+        assert!(value.read_string(&mut [0; 64]).is_err());
+
+        assert_eq!(JSONValue::load("-98").read_integer(), Ok(-98));
+        assert_eq!(JSONValue::load("-99 ").read_integer(), Ok(-99));
+    }
+
+    #[test]
+    fn integer_predicate() {
+        assert!(JSONValue::load("42").is_integer());
+        assert!(JSONValue::load("-7").is_integer());
+        assert!(JSONValue::load("0").is_integer());
+        assert!(JSONValue::load("99999999999999999999").is_integer());
+        assert!(!JSONValue::load("3.14").is_integer());
+        assert!(!JSONValue::load("1e3").is_integer());
+        assert!(!JSONValue::load("1E3").is_integer());
+        assert!(!JSONValue::load("\"42\"").is_integer());
+        assert!(!JSONValue::load("[42]").is_integer());
+        assert!(!JSONValue::load("4x").is_integer());
+    }
+
+    #[test]
+    fn integer_overflow() {
+        assert_eq!(
+            JSONValue::load("99999999999999999999").read_integer(),
+            Err(JSONParsingError::NumberOverflow)
+        );
+        assert_eq!(
+            JSONValue::load("-99999999999999999999").read_integer(),
+            Err(JSONParsingError::NumberOverflow)
+        );
+        let min = std::format!("{}", isize::MIN);
+        assert_eq!(JSONValue::load(&min).read_integer(), Ok(isize::MIN));
+        let max = std::format!("{}", isize::MAX);
+        assert_eq!(JSONValue::load(&max).read_integer(), Ok(isize::MAX));
+        let past_max = std::format!("{}", isize::MAX as u128 + 1);
+        assert_eq!(
+            JSONValue::load(&past_max).read_integer(),
+            Err(JSONParsingError::NumberOverflow)
+        );
+
+        assert_eq!(
+            JSONValue::load("9223372036854775808").read_i64(),
+            Err(JSONParsingError::NumberOverflow)
+        );
+        assert_eq!(
+            JSONValue::load("18446744073709551616").read_u64(),
+            Err(JSONParsingError::NumberOverflow)
+        );
+        assert_eq!(
+            JSONValue::load("-1").read_u64(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+    }
+
+    #[test]
+    fn wide_integers() {
+        assert_eq!(JSONValue::load("9000000000").read_i64(), Ok(9_000_000_000));
+        assert_eq!(
+            JSONValue::load("-9000000000").read_i64(),
+            Ok(-9_000_000_000)
+        );
+        assert_eq!(JSONValue::load("9000000000").read_u64(), Ok(9_000_000_000));
+        assert_eq!(
+            JSONValue::load("-9223372036854775808").read_i64(),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            JSONValue::load("18446744073709551615").read_u64(),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            JSONValue::load("-1").read_u64(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            JSONValue::load("-0").read_u64(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            JSONValue::load("1.5").read_i64(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            JSONValue::load("\"1\"").read_u64(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn float() {
+        let (value, value_len) = JSONValue::parse("3.141592").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Number);
+        assert_eq!(value_len, "3.141592".len());
+        assert_eq!(
+            value.read_integer(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            value.read_string(&mut [0; 64]),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert!((value.read_float().unwrap() - 3.141592).abs() < 0.0001);
+
+        assert_eq!(
+            JSONValue::load("-3.43w").read_float(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+    }
+
+    #[test]
+    fn double_precision_float() {
+        let value = JSONValue::load("3.141592653589793");
+        assert!((value.read_f64().unwrap() - core::f64::consts::PI).abs() < f64::EPSILON);
+        assert_eq!(JSONValue::load("0.1").read_f64(), Ok(0.1));
+        assert_eq!(JSONValue::load("1e300").read_f64(), Ok(1e300));
+        assert_eq!(JSONValue::load("-2.5e-300").read_f64(), Ok(-2.5e-300));
+        assert_eq!(
+            JSONValue::load("9007199254740993").read_f64(),
+            Ok(9007199254740992.0)
+        );
+        assert_eq!(
+            JSONValue::load("true").read_f64(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+        assert_eq!(
+            JSONValue::load("1.5x").read_f64(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+    }
+
+    #[test]
+    fn float_fractions() {
+        for (number, expected) in [
+            ("0.0001", 0.0001),
+            ("123.456", 123.456),
+            ("0.1", 0.1),
+            ("-0.75", -0.75),
+            ("1.23456789", 1.23456789),
+        ] {
+            assert_eq!(JSONValue::load(number).read_float(), Ok(expected as f32));
+            assert_eq!(JSONValue::load(number).read_f64(), Ok(expected));
+        }
+
+        // A trailing point is rejected the same way everywhere
+        assert!(JSONValue::parse("1.").is_err());
+        assert_eq!(
+            JSONValue::load("1.").read_float(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+        assert_eq!(
+            JSONValue::load("1.").read_f64(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn float_exponents() {
+        assert_eq!(JSONValue::load("1e3").read_float(), Ok(1000.0));
+        assert_eq!(JSONValue::load("2.5e-2").read_float(), Ok(0.025));
+        assert_eq!(JSONValue::load("1E+2").read_float(), Ok(100.0));
+        assert_eq!(JSONValue::load("-4e0").read_float(), Ok(-4.0));
+        assert_eq!(JSONValue::load("6.022e23").read_float(), Ok(6.022e23));
+        assert_eq!(JSONValue::load("3.14").read_float(), Ok(3.14));
+        assert_eq!(
+            JSONValue::load("1e").read_float(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+    }
+
+    #[test]
+    fn boolean() {
+        assert_eq!(JSONValue::load("true").read_bool(), Ok(true));
+        assert_eq!(JSONValue::load("false ").read_bool(), Ok(false));
+        assert_eq!(
+            JSONValue::load("[false]")
+                .iter_array()
+                .unwrap()
+                .next()
+                .unwrap()
+                .read_bool(),
+            Ok(false)
+        );
+        assert_eq!(
+            JSONValue::load("1").read_bool(),
+            Err(JSONParsingError::CannotParseBool)
+        );
+        assert_eq!(
+            JSONValue::load("trueish").read_bool(),
+            Err(JSONParsingError::CannotParseBool)
+        );
+    }
+
+    #[test]
+    fn null() {
+        let value = JSONValue::load("null");
+        assert!(value.is_null());
+        assert!(value.as_option().is_none());
+
+        let value = JSONValue::load("\"foo\"");
+        assert!(!value.is_null());
+        assert_eq!(
+            value.as_option().unwrap().read_string(&mut [0; 64]),
+            Ok("foo")
+        );
+
+        let value = JSONValue::load("{\"a\": {\"b\": null}}");
+        assert!(!value.is_null());
+        let inner = value.get_key_value("a").unwrap().as_option().unwrap();
+        assert_eq!(inner.value_type, JSONValueType::Object);
+        assert!(inner.get_key_value("b").unwrap().is_null());
+    }
+
+    #[test]
+    fn string() {
+        let (value, value_len) = JSONValue::parse("\"hello world\"").unwrap();
+        assert_eq!(value.value_type, JSONValueType::String);
+        assert_eq!(value_len, "\"hello world\"".len());
+        assert!(value.read_integer().is_err());
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("hello world"));
+
+        let value = JSONValue::load("\"hello world\"   ");
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("hello world"));
+    }
+
+    #[test]
+    fn array() {
+        let (value, value_len) = JSONValue::parse("[1,2,3]").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Array);
+        assert_eq!(value_len, "[1,2,3]".len());
+        let (value, value_len) = JSONValue::parse("[]").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Array);
+        assert_eq!(value_len, "[]".len());
+        let (value, value_len) = JSONValue::parse("  [\n  ]").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Array);
+        assert_eq!(value_len, "  [\n  ]".len());
+        let (value, value_len) = JSONValue::parse("[1  ,  2\t,\r3\n]").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Array);
+        assert_eq!(value_len, "[1  ,  2\t,\r3\n]".len());
+
+        assert!(value.read_integer().is_err());
+        assert!(value.read_string(&mut [0; 64]).is_err());
+        assert_eq!(
+            value.iter_array().unwrap().next().unwrap().read_integer(),
+            Ok(1)
+        );
+        assert_eq!(
+            value.iter_array().unwrap().nth(1).unwrap().read_integer(),
+            Ok(2)
+        );
+        assert_eq!(
+            value.iter_array().unwrap().nth(2).unwrap().read_integer(),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn array_iterator() {
+        let value = JSONValue::load("[ 1 , [2, 3] ,{\"a\": 4}]");
+        let mut items = value.iter_array().unwrap();
+        assert_eq!(items.next().unwrap().read_integer(), Ok(1));
+        assert_eq!(items.next().unwrap().value_type, JSONValueType::Array);
+        assert_eq!(items.next().unwrap().value_type, JSONValueType::Object);
+        assert!(items.next().is_none());
+        assert!(items.next().is_none());
+
+        assert_eq!(JSONValue::load("[]").iter_array().unwrap().count(), 0);
+        assert_eq!(JSONValue::load(" [ \n ] ").iter_array().unwrap().count(), 0);
+        assert_eq!(
+            JSONValue::load("{}").iter_array().err(),
+            Some(JSONParsingError::CannotParseArray)
+        );
+
+        // Malformed arrays end iteration rather than panicking
+        assert_eq!(JSONValue::load("[1").iter_array().unwrap().count(), 1);
+        assert_eq!(JSONValue::load("[1 2]").iter_array().unwrap().count(), 1);
+        assert_eq!(
+            JSONValue::load("[1\u{e9}]").iter_array().unwrap().count(),
+            1
+        );
+        assert_eq!(JSONValue::load("[1,").iter_array().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn object() {
+        let input = "{
+        \"id\": 0,
+        \"name\": \"Ginger Fuller\"}";
+        let (value, value_len) = JSONValue::parse(input).unwrap();
+        assert_eq!(value.value_type, JSONValueType::Object);
+        assert_eq!(value_len, input.len());
+
+        assert!(value.read_integer().is_err());
+        assert!(value.read_string(&mut [0; 64]).is_err());
+        assert_eq!(value.get_key_value("id").unwrap().read_integer(), Ok(0));
+        assert_eq!(
+            value
+                .get_key_value("name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("Ginger Fuller")
+        );
+        assert_eq!(
+            value.get_key_value("surname").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+
+        assert!(JSONValue::load("{\"foo\":[{}]}").value_type != JSONValueType::Error);
+        assert!(JSONValue::load("[{\"foo\":{}}]").value_type != JSONValueType::Error);
+    }
+    #[test]
+
+    fn this_broke_once() {
+        assert!(
+            JSONValue::load(
+                r##"
+[{"a":{"email":"d@"},"m":"#20\n\n.\n"}]
+    "##
+            )
+            .value_type
+                != JSONValueType::Error
+        )
+    }
+
+    #[test]
+    fn integer_whitespace() {
+        let (value, value_len) = JSONValue::parse("  42	").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Number);
+        assert_eq!(value_len, "  42".len());
+        let (value, value_len) = JSONValue::parse("\n 42\r").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Number);
+        assert_eq!(value_len, "\n 42".len());
+    }
+
+    #[test]
+    fn string_whitespace() {
+        let (value, value_len) = JSONValue::parse("  \"foo me a bar\"	").unwrap();
+        assert_eq!(value.value_type, JSONValueType::String);
+        assert_eq!(value_len, "  \"foo me a bar\"".len());
+        let (value, value_len) = JSONValue::parse("\n \"a bar\\n I said.\"\r").unwrap();
+        assert_eq!(value.value_type, JSONValueType::String);
+        assert_eq!(value_len, "\n \"a bar\\n I said.\"".len());
+    }
+
+    #[test]
+    fn peeking_value_type() {
+        assert_eq!(
+            JSONValue::peek_value_type("123", Parser::new()),
+            JSONValueType::Number
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("12.3", Parser::new()),
+            JSONValueType::Number
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("12.3e10", Parser::new()),
+            JSONValueType::Number
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("-3", Parser::new()),
+            JSONValueType::Number
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("-3.5", Parser::new()),
+            JSONValueType::Number
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("null", Parser::new()),
+            JSONValueType::Null
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("true", Parser::new()),
+            JSONValueType::Bool
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("false", Parser::new()),
+            JSONValueType::Bool
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("[]", Parser::new()),
+            JSONValueType::Array
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("[12]", Parser::new()),
+            JSONValueType::Array
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("[1,2]", Parser::new()),
+            JSONValueType::Array
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("[[]]", Parser::new()),
+            JSONValueType::Array
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("\"foo\"", Parser::new()),
+            JSONValueType::String
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("{}", Parser::new()),
+            JSONValueType::Object
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("{\"a\":2}", Parser::new()),
+            JSONValueType::Object
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("<", Parser::new()),
+            JSONValueType::Error
+        );
+        assert_eq!(
+            JSONValue::peek_value_type("bar", Parser::new()),
+            JSONValueType::Error
+        );
+
+        // Values using the parser's extensions to JSON are only recognised when they are allowed
+        for (contents, value_type) in [
+            ("'foo'", JSONValueType::String),
+            ("+1", JSONValueType::Number),
+            (".5", JSONValueType::Number),
+            ("NaN", JSONValueType::Number),
+            ("Infinity", JSONValueType::Number),
+        ] {
+            assert_eq!(
+                JSONValue::peek_value_type(contents, Parser::new()),
+                JSONValueType::Error
+            );
+            assert_eq!(
+                JSONValue::peek_value_type(contents, Parser::lenient()),
+                value_type
+            );
+        }
+    }
+
+    #[test]
+    fn verifying() {
+        assert!(JSONValue::load_and_verify(" 123 ").is_ok());
+        assert!(JSONValue::load_and_verify("[123]").is_ok());
+        assert!(JSONValue::load_and_verify("\"foo\"").is_ok());
+    }
+
+    #[test]
+    fn error_offsets() {
+        let error = JSONValue::load("[1,2,]").verify().unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 5);
+
+        let error = JSONValue::load_and_verify("  {\"a\": [1, x]}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 12);
+
+        let error = JSONValue::load_and_verify("{\"a\":1,}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 7);
+
+        let error = JSONValue::load_and_verify("{\"a\" 1}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 5);
+
+        let error = JSONValue::load_and_verify("[1, 2").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::EndOfStream);
+        assert_eq!(error.offset, 5);
+
+        // Strings must end with an unescaped quote, however the input is cut off
+        for contents in [
+            r#""abc"#,
+            r#""abc\"#,
+            r#""abc\""#,
+            r#""abc\\\""#,
+            r#"["abc\"]"#,
+        ] {
+            let error = JSONValue::parse(contents).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::EndOfStream, "{}", contents);
+            assert_eq!(error.offset, contents.len(), "{}", contents);
+        }
+        assert!(JSONValue::parse(r#""abc\\""#).is_ok());
+    }
+
+    #[test]
+    fn string_iterator() {
+        let try_parse_string = |s| {
+            JSONValue::load(s)
+                .iter_string()
+                .unwrap()
+                .collect::<Result<std::string::String, _>>()
+        };
+        let value = try_parse_string("\"I have a dream\"").unwrap();
+        assert_eq!(value, "I have a dream");
+
+        let value = try_parse_string("\"\\\"I have a dream\\\"\"").unwrap();
+        assert_eq!(value, "\"I have a dream\"");
+
+        let value = try_parse_string(r#" "\"I\n\thave\b\fa\\dream\/\"\u00a3" "#).unwrap();
+        assert_eq!(value, "\"I\n\thave\x08\x0ca\\dream/\"£");
+
+        let value = try_parse_string(r#" " "#);
+        assert!(matches!(value, Err(JSONParsingError::EndOfStream)));
+        let value = try_parse_string(r#" "foo\" "#);
+        assert!(matches!(value, Err(JSONParsingError::EndOfStream)));
+        let value = try_parse_string(r#" "Odd escape: \?" "#);
+        assert!(matches!(
+            value,
+            Err(JSONParsingError::InvalidEscapeSequence('?'))
+        ));
+        let value = try_parse_string(r#" "\uwxyz" "#);
+        assert!(matches!(
+            value,
+            Err(JSONParsingError::InvalidUnicodeEscapeSequence)
+        ));
+        let value = try_parse_string(r#" "\u012" "#);
+        assert!(matches!(
+            value,
+            Err(JSONParsingError::TooShortEscapeSequence)
+        ));
+        // This is not a single character codepoint under utf-16
+        let value = try_parse_string(r#" "\ud834" "#);
+        assert!(matches!(
+            value,
+            Err(JSONParsingError::InvalidUnicodeEscapeSequence)
+        ));
+    }
+
+    #[test]
+    fn object_iterator() {
+        let json_value = JSONValue::load("{\"foo\" : [], \"bar\":{\"baz\": 2}}");
+        let keys = ["foo", "bar"];
+        for (item, expected_key) in json_value.iter_object().unwrap().zip(&keys) {
+            assert_eq!(item.unwrap().0, *expected_key);
+        }
+    }
+
+    #[test]
+    fn object_iterator_entries() {
+        let value = JSONValue::load("{\"a\":1,\"b\":2}");
+        let (keys, values): (std::vec::Vec<_>, std::vec::Vec<_>) = value
+            .iter_object()
+            .unwrap()
+            .map(|item| item.unwrap())
+            .unzip();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(values[0].read_integer(), Ok(1));
+        assert_eq!(values[1].read_integer(), Ok(2));
+
+        let value = JSONValue::load("{ \"a\" :\n{\"c\": 3} ,\t\"b\"\r: [ ] }");
+        let mut items = value.iter_object().unwrap();
+        let (key, inner) = items.next().unwrap().unwrap();
+        assert_eq!(key, "a");
+        assert_eq!(inner.get_key_value("c").unwrap().read_integer(), Ok(3));
+        let (key, inner) = items.next().unwrap().unwrap();
+        assert_eq!(key, "b");
+        assert_eq!(inner.value_type, JSONValueType::Array);
+        assert!(items.next().is_none());
+
+        assert_eq!(JSONValue::load("{}").iter_object().unwrap().count(), 0);
+        assert_eq!(JSONValue::load("{ }").iter_object().unwrap().count(), 0);
+
+        // Malformed objects yield an error or end iteration rather than panicking
+        let mut items = JSONValue::load("{\"a\"").iter_object().unwrap();
+        assert!(matches!(
+            items.next(),
+            Some(Err(JSONParsingError::UnexpectedToken))
+        ));
+        assert!(items.next().is_none());
+        let mut items = JSONValue::load("{\"a\" 1}").iter_object().unwrap();
+        assert!(matches!(
+            items.next(),
+            Some(Err(JSONParsingError::UnexpectedToken))
+        ));
+        let mut items = JSONValue::load("{\"a\":1\u{e9}}").iter_object().unwrap();
+        assert!(items.next().unwrap().is_ok());
+        assert!(matches!(
+            items.next(),
+            Some(Err(JSONParsingError::UnexpectedToken))
+        ));
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn key_iterator() {
+        let value = JSONValue::load("{\"outer\":{\"inner\":1},\"x\":2}");
+        let keys: Result<std::vec::Vec<_>, _> = value.iter_keys().unwrap().collect();
+        assert_eq!(keys, Ok(std::vec!["outer", "x"]));
+
+        let value = JSONValue::load("{\"a\": [{\"b\": 1}], \"c\": \"d\"}");
+        let keys: Result<std::vec::Vec<_>, _> = value.iter_keys().unwrap().collect();
+        assert_eq!(keys, Ok(std::vec!["a", "c"]));
+
+        assert_eq!(JSONValue::load("{}").iter_keys().unwrap().count(), 0);
+        assert!(JSONValue::load("[]").iter_keys().is_err());
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(JSONValue::load("[]").len(), Ok(0));
+        assert_eq!(JSONValue::load("{}").len(), Ok(0));
+        assert_eq!(JSONValue::load("[]").is_empty(), Ok(true));
+        assert_eq!(JSONValue::load("[1, 2, 3]").len(), Ok(3));
+        assert_eq!(JSONValue::load("[[1, 2], [], {\"a\": [3]}]").len(), Ok(3));
+        assert_eq!(
+            JSONValue::load("{\"a\": {\"b\": 1, \"c\": 2}, \"d\": []}").len(),
+            Ok(2)
+        );
+        assert_eq!(JSONValue::load("{\"a\": 1}").is_empty(), Ok(false));
+        assert_eq!(
+            JSONValue::load("\"abc\"").len(),
+            Err(JSONParsingError::NotAContainer)
+        );
+        assert_eq!(
+            JSONValue::load("null").is_empty(),
+            Err(JSONParsingError::NotAContainer)
+        );
+        assert_eq!(
+            JSONValue::load("{\"a\": }").len(),
+            Err(JSONParsingError::UnexpectedToken)
+        );
+    }
+
+    #[test]
+    fn containing_keys() {
+        let value = JSONValue::load("{\"a\": 1, \"b\": {\"c\": 2}, \"d\": [{\"e\": 3}]}");
+        assert!(value.contains_key("a"));
+        assert!(value.contains_key("b"));
+        assert!(value.contains_key("d"));
+        assert!(!value.contains_key("c"));
+        assert!(!value.contains_key("e"));
+        assert!(!value.contains_key("z"));
+        assert!(!JSONValue::load("[\"a\"]").contains_key("a"));
+        assert!(!JSONValue::load("\"a\"").contains_key("a"));
+    }
+
+    #[test]
+    fn nth_array_item() {
+        let value = JSONValue::load("[1, [2], 3]");
+        assert_eq!(value.get_nth_array_item(0).unwrap().read_integer(), Ok(1));
+        assert_eq!(value.get_nth_array_item(2).unwrap().read_integer(), Ok(3));
+        assert_eq!(
+            value.get_nth_array_item(3).err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            value.get_nth_array_item(usize::MAX).err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            JSONValue::load("[]").get_nth_array_item(0).err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            JSONValue::load("{}").get_nth_array_item(0).err(),
+            Some(JSONParsingError::CannotParseArray)
+        );
+    }
+
+    #[test]
+    fn display() {
+        use std::string::ToString;
+        let text = "{\"a\": [1, 2.5, \"x\\ny\"],\n  \"b\": {\"c\": null}}";
+        let padded = std::format!(" \n{}\t\n", text);
+        let (value, _) = JSONValue::parse(&padded).unwrap();
+        assert_eq!(value.to_string(), text);
+        assert_eq!(JSONValue::load(text).to_string(), text);
+        let item = value
+            .get_key_value("a")
+            .unwrap()
+            .get_nth_array_item(2)
+            .unwrap();
+        assert_eq!(item.to_string(), "\"x\\ny\"");
+        assert_eq!(JSONValue::load(" 42 , 43 ").to_string(), "42");
+        assert_eq!(JSONValue::load("[1, x] ").to_string(), "[1, x]");
+    }
+
+    #[test]
+    fn type_names() {
+        for (contents, value_type, name) in [
+            ("\"a\"", JSONValueType::String, "string"),
+            ("-1.5", JSONValueType::Number, "number"),
+            ("{}", JSONValueType::Object, "object"),
+            ("[]", JSONValueType::Array, "array"),
+            ("false", JSONValueType::Bool, "bool"),
+            ("null", JSONValueType::Null, "null"),
+            ("?", JSONValueType::Error, "invalid"),
+        ] {
+            let value = JSONValue::load(contents);
+            assert_eq!(value.value_type, value_type);
+            assert_eq!(value.type_name(), name);
+        }
+    }
+
+    #[test]
+    fn type_predicates() {
+        for contents in ["\"a\"", "1", "{}", "[]", "true", "null", "?"] {
+            let value = JSONValue::load(contents);
+            let predicates = [
+                value.is_string(),
+                value.is_number(),
+                value.is_object(),
+                value.is_array(),
+                value.is_bool(),
+                value.is_null(),
+            ];
+            let expected = match value.value_type {
+                JSONValueType::Error => 0,
+                _ => 1,
+            };
+            assert_eq!(
+                predicates.iter().filter(|&&p| p).count(),
+                expected,
+                "{}",
+                contents
+            );
+        }
+        assert!(JSONValue::load("\"a\"").is_string());
+        assert!(JSONValue::load("-1e5").is_number());
+        assert!(JSONValue::load("{\"a\": []}").is_object());
+        assert!(JSONValue::load("[{}]").is_array());
+        assert!(JSONValue::load("false").is_bool());
+    }
+
+    #[test]
+    fn finding_indices() {
+        let value = JSONValue::load("[1, 2, 3, 4]");
+        assert_eq!(
+            value.find_index(|item| item.read_integer().unwrap() > 2),
+            Some(2)
+        );
+        assert_eq!(
+            value.find_index(|item| item.read_integer() == Ok(1)),
+            Some(0)
+        );
+        assert_eq!(
+            value.find_index(|item| item.read_integer().unwrap() > 4),
+            None
+        );
+        assert_eq!(JSONValue::load("[]").find_index(|_| true), None);
+
+        let value = JSONValue::load(r#"[{"id": "a"}, {"id": "b"}]"#);
+        assert_eq!(
+            value.find_index(
+                |item| item.get("id").and_then(|id| id.read_string_raw().ok()) == Some("b")
+            ),
+            Some(1)
+        );
+        assert_eq!(JSONValue::load(r#"{"a": 1}"#).find_index(|_| true), None);
+        assert_eq!(JSONValue::load("1").find_index(|_| true), None);
+    }
+
+    #[test]
+    fn repeated_keys() {
+        let value = JSONValue::load(r#"{"a":1,"b":2,"a":3}"#);
+        let values: std::vec::Vec<_> = value
+            .values_for_key("a")
+            .map(|value| value.read_integer().unwrap())
+            .collect();
+        assert_eq!(values, [1, 3]);
+        assert_eq!(value.values_for_key("b").count(), 1);
+        assert_eq!(value.values_for_key("c").count(), 0);
+
+        let value = JSONValue::load(r#"{"a": {"a": 1}, "b": [{"a": 2}], "\u0061": 3}"#);
+        let values: std::vec::Vec<_> = value.values_for_key("a").collect();
+        assert_eq!(values.len(), 2);
+        assert!(values[0].is_object());
+        assert_eq!(values[1].read_integer(), Ok(3));
+
+        assert_eq!(
+            JSONValue::load(r#"{"a": 1, "a" 2}"#)
+                .values_for_key("a")
+                .count(),
+            1
+        );
+        assert_eq!(JSONValue::load("[1]").values_for_key("a").count(), 0);
+    }
+
+    #[test]
+    fn case_insensitive_keys() {
+        let value = JSONValue::load(
+            r#"{"content-type": "text/plain", "X-ID": 1, "\u00c9t\u00e9": 2, "\u0041b": 3}"#,
+        );
+        assert_eq!(
+            value
+                .get_key_value_ci("Content-Type")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("text/plain")
+        );
+        assert_eq!(
+            value.get_key_value_ci("x-id").unwrap().read_integer(),
+            Ok(1)
+        );
+        assert_eq!(value.get_key_value_ci("aB").unwrap().read_integer(), Ok(3));
+        assert_eq!(
+            value
+                .get_key_value_ci("\u{c9}T\u{e9}")
+                .unwrap()
+                .read_integer(),
+            Ok(2)
+        );
+        assert_eq!(
+            value.get_key_value_ci("\u{e9}t\u{e9}").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_key_value_ci("content_type").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            JSONValue::load("[]").get_key_value_ci("a").err(),
+            Some(JSONParsingError::CannotParseObject)
+        );
+    }
+
+    #[test]
+    fn optional_lookups() {
+        let value = JSONValue::load(r#"{"a": 1, "b": [true, null]}"#);
+        assert_eq!(value.get("a").unwrap().read_integer(), Ok(1));
+        assert!(value.get("c").is_none());
+        let array = value.get("b").unwrap();
+        assert_eq!(array.get_index(0).unwrap().read_bool(), Ok(true));
+        assert!(array.get_index(1).unwrap().is_null());
+        assert!(array.get_index(2).is_none());
+
+        // Type mismatches give None rather than panicking
+        assert!(array.get("a").is_none());
+        assert!(value.get_index(0).is_none());
+        assert!(JSONValue::load("1").get("a").is_none());
+        assert!(JSONValue::load("\"a\"").get_index(0).is_none());
+    }
+
+    #[test]
+    fn escaped_keys() {
+        let value = JSONValue::load(r#"{"a\u0062": 1, "say \"hi\"": 2, "tab\t": 3}"#);
+        assert_eq!(value.get_key_value("ab").unwrap().read_integer(), Ok(1));
+        assert_eq!(
+            value.get_key_value("say \"hi\"").unwrap().read_integer(),
+            Ok(2)
+        );
+        assert_eq!(value.get_key_value("tab\t").unwrap().read_integer(), Ok(3));
+        assert!(value.contains_key("ab"));
+        assert_eq!(
+            value.get_key_value("a\\u0062").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_key_value("a").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(value.get_path("ab").unwrap().read_integer(), Ok(1));
+
+        // Keys with escapes that can't be decoded are malformed, not just unmatched
+        let value = JSONValue::load(r#"{"a": 1, "x\q": 4}"#);
+        assert_eq!(value.get_key_value("a").unwrap().read_integer(), Ok(1));
+        assert_eq!(
+            value.get_key_value("x\\q").err(),
+            Some(JSONParsingError::InvalidEscapeSequence('q'))
+        );
+    }
+
+    #[test]
+    fn malformed_key_value() {
+        let get = |input, key| JSONValue::load(input).get_key_value(key).err();
+        assert_eq!(
+            get("{\"a\":}", "a"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(
+            get("{\"a\":}", "b"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(get("{\"a\"}", "a"), Some(JSONParsingError::UnexpectedToken));
+        assert_eq!(
+            get("{\"a\" 1}", "a"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(
+            get("{\"a\":1 \"b\":2}", "b"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(get("{1:2}", "a"), Some(JSONParsingError::UnexpectedToken));
+        assert_eq!(
+            get("{\"a\":[1,}", "a"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(
+            get("{\"a\":1,\"b\"", "b"),
+            Some(JSONParsingError::UnexpectedToken)
+        );
+        assert_eq!(get("{\"a\":1,", "b"), Some(JSONParsingError::EndOfStream));
+        assert_eq!(get("{", "a"), Some(JSONParsingError::EndOfStream));
+        assert_eq!(get("{\"a\":1", "a"), None);
+
+        let mut members = JSONValue::load("{\"a\":1,").iter_object().unwrap();
+        assert!(matches!(members.next(), Some(Ok(("a", _)))));
+        assert_eq!(
+            members.next().unwrap().unwrap_err(),
+            JSONParsingError::EndOfStream
+        );
+        assert!(members.next().is_none());
+    }
+
+    #[test]
+    fn control_characters_in_strings() {
+        let error = JSONValue::parse("\"a\tb\"").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidStringCharacter);
+        assert_eq!(error.offset, 2);
+        let error = JSONValue::parse("[\"a\", {\"b\nc\": 1}]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidStringCharacter);
+        assert_eq!(error.offset, 9);
+        assert!(JSONValue::parse("\"\u{0}\"").is_err());
+        assert!(JSONValue::parse("\"\u{1f}\"").is_err());
+
+        let value = JSONValue::load_and_verify(r#""a\tb""#).unwrap();
+        assert!(value.iter_string().unwrap().eq("a\tb".chars().map(Ok)));
+        assert!(JSONValue::parse("\"\u{7f}\"").is_ok());
+        assert!(JSONValue::load_and_verify(r##"[{"a":{"email":"d@"},"m":"#20\n\n.\n"}]"##).is_ok());
+    }
+
+    #[test]
+    fn number_grammar() {
+        for (number, offset) in [
+            ("--5", 1),
+            ("1.2.3", 3),
+            ("1ee5", 2),
+            (".5", 0),
+            ("+3", 0),
+            ("-", 1),
+            ("01", 1),
+            ("-00", 2),
+            ("1.", 2),
+            ("1.e5", 2),
+            ("1e", 2),
+            ("1e+", 3),
+            ("1e5e5", 3),
+            ("2-1", 1),
+        ] {
+            let error = JSONValue::parse(number).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::InvalidNumber, "{}", number);
+            assert_eq!(error.offset, offset, "{}", number);
+        }
+        let error = JSONValue::parse("[1, 1.2.3]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidNumber);
+        assert_eq!(error.offset, 7);
+
+        for number in [
+            "0",
+            "-0",
+            "1e10",
+            "3.14e-2",
+            "0.5",
+            "1E+2",
+            "-0.0e0",
+            "10",
+            "123456789",
+        ] {
+            let (_, value_len) = JSONValue::parse(number).unwrap();
+            assert_eq!(value_len, number.len());
+        }
+        assert!(JSONValue::parse_exact("[0,-1,2.5e3]").is_ok());
+    }
+
+    #[test]
+    fn leading_zeros() {
+        for number in ["01", "-03", "00", "-00", "007", "01.5"] {
+            let error = JSONValue::parse(number).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::InvalidNumber, "{}", number);
+        }
+        for number in ["0", "0.0", "0.5", "-0", "10", "0e5"] {
+            assert!(JSONValue::parse_exact(number).is_ok(), "{}", number);
+        }
+
+        // Accessors reject them too, rather than reading a different number
+        assert_eq!(
+            JSONValue::load("007").read_integer(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            JSONValue::load("-03.5").read_float(),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+        assert_eq!(JSONValue::load("0").read_integer(), Ok(0));
+        assert_eq!(JSONValue::load("-0 ").read_integer(), Ok(0));
+    }
+
+    #[test]
+    fn string_escapes() {
+        let value = JSONValue::load(r#""a\tb""#);
+        let chars: std::vec::Vec<_> = value.iter_string().unwrap().collect();
+        assert_eq!(chars, [Ok('a'), Ok('\t'), Ok('b')]);
+
+        let value = JSONValue::load(r#""\"\\\/\b\f\n\r\t\u0041""#);
+        assert!(value
+            .iter_string()
+            .unwrap()
+            .eq("\"\\/\u{8}\u{c}\n\r\tA".chars().map(Ok)));
+
+        // Truncated strings end with an error rather than quietly
+        let mut chars = JSONValue::load(r#""ab\"#).iter_string().unwrap();
+        assert_eq!(chars.next(), Some(Ok('a')));
+        assert_eq!(chars.next(), Some(Ok('b')));
+        assert_eq!(chars.next(), Some(Err(JSONParsingError::EndOfStream)));
+        assert_eq!(chars.next(), None);
+        let mut chars = JSONValue::load(r#""a"#).iter_string().unwrap();
+        assert_eq!(chars.next(), Some(Ok('a')));
+        assert_eq!(chars.next(), Some(Err(JSONParsingError::EndOfStream)));
+        assert_eq!(chars.next(), None);
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        let decode = |string| {
+            JSONValue::load(string)
+                .iter_string()
+                .unwrap()
+                .collect::<Result<std::string::String, _>>()
+        };
+        assert_eq!(decode(r#""\u00e9""#).unwrap(), "\u{e9}");
+        assert_eq!(decode(r#""\u00E9\uffFD""#).unwrap(), "\u{e9}\u{fffd}");
+        assert_eq!(decode(r#""\uD83D\uDE00!""#).unwrap(), "\u{1f600}!");
+        assert_eq!(decode(r#""\ud834\udd1e""#).unwrap(), "\u{1d11e}");
+
+        for string in [
+            r#""\uD83D""#,
+            r#""\uD83Dx""#,
+            r#""\uD83D\n""#,
+            r#""\uD83D\u0041""#,
+            r#""\uD83D\uD83D""#,
+            r#""\uDE00""#,
+            r#""\u00g9""#,
+            r#""\u-123""#,
+        ] {
+            assert_eq!(
+                decode(string),
+                Err(JSONParsingError::InvalidUnicodeEscapeSequence),
+                "{}",
+                string
+            );
+        }
+        assert_eq!(
+            decode(r#""\u00""#),
+            Err(JSONParsingError::TooShortEscapeSequence)
+        );
+        assert_eq!(
+            decode(r#""\uD83D\uDE""#),
+            Err(JSONParsingError::TooShortEscapeSequence)
+        );
+    }
+
+    #[test]
+    fn unescaping_into_buffer() {
+        let value = JSONValue::load(r#""a\tb\u00e9\uD83D\uDE00\"""#);
+        let decoded = "a\tb\u{e9}\u{1f600}\"";
+        let mut buf = [0; 32];
+        let len = value.unescape_into(&mut buf).unwrap();
+        assert_eq!(&buf[..len], decoded.as_bytes());
+
+        let mut exact = [0; 10];
+        assert_eq!(value.unescape_into(&mut exact), Ok(decoded.len()));
+        assert_eq!(&exact, decoded.as_bytes());
+
+        // Running out of room part way through a character leaves what was written valid
+        let mut small = [0xff; 8];
+        assert_eq!(
+            value.unescape_into(&mut small),
+            Err(JSONParsingError::BufferTooSmall)
+        );
+        assert_eq!(&small[..5], "a\tb\u{e9}".as_bytes());
+        assert_eq!(&small[5..], [0xff; 3]);
+        assert_eq!(
+            value.unescape_into(&mut []),
+            Err(JSONParsingError::BufferTooSmall)
+        );
+
+        assert_eq!(JSONValue::load(r#""""#).unescape_into(&mut []), Ok(0));
+        assert_eq!(
+            JSONValue::load("1").unescape_into(&mut buf),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\x""#).unescape_into(&mut buf),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            JSONValue::load("\"a\tb\"").unescape_into(&mut buf),
+            Err(JSONParsingError::InvalidStringCharacter)
+        );
+    }
+
+    #[test]
+    fn decoded_string_length() {
+        for (string, decoded) in [
+            (r#""a\nb""#, "a\nb"),
+            (r#""\u00e9""#, "\u{e9}"),
+            (r#""\u20AC""#, "\u{20ac}"),
+            (r#""\uD83D\uDE00""#, "\u{1f600}"),
+            (r#""\"\\\/\b\f\r\t""#, "\"\\/\u{8}\u{c}\r\t"),
+            (r#""plain""#, "plain"),
+            (r#""""#, ""),
+        ] {
+            let value = JSONValue::load(string);
+            assert_eq!(value.string_decoded_len(), Ok(decoded.len()), "{}", string);
+            let chars: Result<std::string::String, _> = value.iter_string().unwrap().collect();
+            assert_eq!(chars.unwrap(), decoded);
+        }
+        assert_eq!(
+            JSONValue::load(r#""\q""#).string_decoded_len(),
+            Err(JSONParsingError::InvalidEscapeSequence('q'))
+        );
+        assert_eq!(
+            JSONValue::load("1").string_decoded_len(),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load("\"a\tb\"").string_decoded_len(),
+            Err(JSONParsingError::InvalidStringCharacter)
+        );
+    }
+
+    #[test]
+    fn parsing_bytes() {
+        let (value, value_len) = JSONValue::parse_bytes(b"{\"a\":1}").unwrap();
+        let (expected, expected_len) = JSONValue::parse("{\"a\":1}").unwrap();
+        assert_eq!(value_len, expected_len);
+        assert_eq!(value.value_type, expected.value_type);
+        assert_eq!(value.get_key_value("a").unwrap().read_integer(), Ok(1));
+        assert!(value.semantic_eq(&expected));
+
+        let (value, _) = JSONValue::parse_bytes("\"caf\u{e9}\"".as_bytes()).unwrap();
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("caf\u{e9}"));
+
+        let error = JSONValue::parse_bytes(b"[1, \xc3]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidUtf8);
+        assert_eq!(error.offset, 4);
+        let error = JSONValue::parse_bytes(b"[1, ").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::EndOfStream);
+        let error = JSONValue::parse_bytes(b"  [1, x]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 6);
+
+        // Each malformed sequence is reported at its first byte
+        let cases: [(&[u8], usize); 7] = [
+            (b"\"ab\xe2\x82", 3),
+            (b"\"\xe2\x82\xac\xf0\x9f\x98\"", 4),
+            (b"[\"\xc3\x28\"]", 2),
+            (b"\"\xe2\x28\xa1\"", 1),
+            (b"\"\xc0\xaf\"", 1),
+            (b"\"\xed\xa0\x80\"", 1),
+            (b"\"\x80\"", 1),
+        ];
+        for (bytes, offset) in cases {
+            let error = JSONValue::parse_bytes(bytes).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::InvalidUtf8, "{:?}", bytes);
+            assert_eq!(error.offset, offset, "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn parsing_at_offsets() {
+        let contents = "12 \"ab\"\n[3]";
+        let (first, offset) = JSONValue::parse_at(contents, 0).unwrap();
+        assert_eq!(first.read_integer(), Ok(12));
+        assert_eq!(offset, 2);
+        let (second, offset) = JSONValue::parse_at(contents, offset).unwrap();
+        assert_eq!(second.read_string(&mut [0; 64]), Ok("ab"));
+        assert_eq!(offset, 7);
+        let (third, offset) = JSONValue::parse_at(contents, offset).unwrap();
+        assert_eq!(third.get_nth_array_item(0).unwrap().read_integer(), Ok(3));
+        assert_eq!(offset, contents.len());
+
+        let error = JSONValue::parse_at(contents, offset).unwrap_err();
+        assert_eq!(error.offset, contents.len());
+        let error = JSONValue::parse_at("[1] [2, x]", 3).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 8);
+        let error = JSONValue::parse_at("1", 2).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::EndOfStream);
+        assert_eq!(error.offset, 1);
+        let error = JSONValue::parse_at("\"\u{e9}\"", 2).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 2);
+    }
+
+    #[test]
+    fn value_streams() {
+        let values: std::vec::Vec<_> = JSONValue::parse_stream("1 2 3")
+            .map(|value| value.unwrap().read_integer().unwrap())
+            .collect();
+        assert_eq!(values, [1, 2, 3]);
+
+        let lines = "{\"a\": 1}\n{\"a\": [2]}\r\n\n{\"a\": \"3\"}\n";
+        let values: std::vec::Vec<_> = JSONValue::parse_stream(lines)
+            .map(|value| value.unwrap().get_key_value("a").unwrap())
+            .collect();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].read_integer(), Ok(1));
+        assert_eq!(values[1].value_type, JSONValueType::Array);
+        assert_eq!(values[2].read_string(&mut [0; 64]), Ok("3"));
+
+        assert_eq!(JSONValue::parse_stream("[1][2]{}\"x\"").count(), 4);
+        assert_eq!(JSONValue::parse_stream("").count(), 0);
+        assert_eq!(JSONValue::parse_stream(" \n ").count(), 0);
+        assert_eq!(JSONValue::parse_stream("\u{feff}1 2").count(), 2);
+
+        let mut stream = JSONValue::parse_stream("1\n[2,]\n3");
+        assert!(stream.next().unwrap().is_ok());
+        let error = stream.next().unwrap().unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 5);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn validity() {
+        for contents in [
+            "0",
+            " true ",
+            "\"\\u00e9\"",
+            "[]",
+            "[1, -2.5e3, \"x\", null, {}]",
+            "{\"a\": {\"b\": [false]}}\r\n",
+        ] {
+            assert!(JSONValue::is_valid(contents), "{}", contents);
+        }
+        for contents in [
+            "",
+            " ",
+            "{} {}",
+            "[1,]",
+            "{\"a\":1,}",
+            "[1, 2",
+            "{\"a\"",
+            "\"abc",
+            "tru",
+            "01",
+            "1.",
+            "\"a\tb\"",
+            "{a: 1}",
+            "[1] x",
+        ] {
+            assert!(!JSONValue::is_valid(contents), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn exact_parsing() {
+        assert!(JSONValue::parse_exact("42").is_ok());
+        assert!(JSONValue::parse_exact("  {\"a\": 1} \r\n\t").is_ok());
+        assert_eq!(
+            JSONValue::parse_exact("42 ").unwrap().read_integer(),
+            Ok(42)
+        );
+
+        let error = JSONValue::parse_exact("42,").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 2);
+        let error = JSONValue::parse_exact("{} {}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 3);
+        assert!(JSONValue::parse_exact("42 garbage").is_err());
+        assert!(JSONValue::parse_exact("").is_err());
+    }
+
+    #[test]
+    fn truncated_literals() {
+        for input in [
+            "t",
+            "tru",
+            "nul",
+            "fals",
+            "f",
+            "n",
+            "tr\u{e9}",
+            "[t",
+            "{\"a\":nul",
+        ] {
+            assert!(
+                JSONValue::load(input).verify().is_err(),
+                "{:?} should not verify",
+                input
+            );
+        }
+        let error = JSONValue::load_and_verify("[true, fals]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 7);
+        assert!(JSONValue::load_and_verify("[true, false, null]").is_ok());
+    }
+
+    #[test]
+    fn raw_strings() {
+        let value = JSONValue::load(r#""a\nb""#);
+        assert_eq!(value.read_string_raw(), Ok(r"a\nb"));
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("a\nb"));
+        assert!(value.iter_string().unwrap().eq("a\nb".chars().map(Ok)));
+
+        let value = JSONValue::load(r#""caf\u00e9 \"quoted\"""#);
+        assert_eq!(value.read_string_raw(), Ok(r#"caf\u00e9 \"quoted\""#));
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("caf\u{e9} \"quoted\""));
+        assert_eq!(
+            value.read_string(&mut [0; 8]),
+            Err(JSONParsingError::BufferTooSmall)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\x""#).read_string(&mut [0; 64]),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+
+        let value = JSONValue::load("\"plain \u{e9}\"");
+        assert_eq!(value.read_string_raw(), Ok("plain \u{e9}"));
+        assert_eq!(value.read_string(&mut [0; 64]), Ok("plain \u{e9}"));
+        assert_eq!(
+            JSONValue::load("1").read_string_raw(),
+            Err(JSONParsingError::CannotParseString)
+        );
+    }
+
+    #[test]
+    fn string_borrow_past_lifetime_of_value() {
+        let s = "\"abc\"";
+        let t: &str;
+        {
+            t = JSONValue::load(s).read_string_raw().unwrap();
+        }
+        assert_eq!(t, &s[1..s.len() - 1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_strings() {
+        let value = JSONValue::load(r#""caf\u00e9""#);
+        assert_eq!(value.read_string_owned().unwrap(), "caf\u{e9}");
+        let value = JSONValue::load(r#""\"quoted\"\t\ud834\udd1e\/""#);
+        assert_eq!(value.read_string_owned().unwrap(), "\"quoted\"\t\u{1d11e}/");
+        assert_eq!(JSONValue::load(r#""""#).read_string_owned().unwrap(), "");
+
+        assert_eq!(
+            JSONValue::load(r#""\ud834""#).read_string_owned(),
+            Err(JSONParsingError::InvalidUnicodeEscapeSequence)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\x""#).read_string_owned(),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            JSONValue::load(r#""unterminated"#).read_string_owned(),
+            Err(JSONParsingError::EndOfStream)
+        );
+        assert_eq!(
+            JSONValue::load("42").read_string_owned(),
+            Err(JSONParsingError::CannotParseString)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn array_vec() {
+        let items = JSONValue::load("[1,2,3]").to_array_vec().unwrap();
+        assert_eq!(items.len(), 3);
+        for (n, item) in items.iter().enumerate() {
+            assert_eq!(item.read_integer(), Ok(n as isize + 1));
+        }
+        assert!(JSONValue::load(" [ ] ").to_array_vec().unwrap().is_empty());
+        let items = JSONValue::load(r#"[{"a": [1]}, null]"#)
+            .to_array_vec()
+            .unwrap();
+        assert_eq!(items[0].value_type, JSONValueType::Object);
+        assert_eq!(items[1].value_type, JSONValueType::Null);
+
+        assert_eq!(
+            JSONValue::load(r#"{"a": 1}"#).to_array_vec().unwrap_err(),
+            JSONParsingError::CannotParseArray
+        );
+        assert_eq!(
+            JSONValue::load("[1, 2, x]").to_array_vec().unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+        assert_eq!(
+            JSONValue::load("[1, 2").to_array_vec().unwrap_err(),
+            JSONParsingError::EndOfStream
+        );
+    }
+
+    #[test]
+    fn number_array_into() {
+        let value = JSONValue::load("[0.5, -2, 1e3]");
+        let mut out = [0.0; 3];
+        assert_eq!(value.read_number_array_into(&mut out), Ok(3));
+        assert_eq!(out, [0.5, -2.0, 1000.0]);
+
+        let mut out = [9.0; 2];
+        assert_eq!(value.read_number_array_into(&mut out), Ok(2));
+        assert_eq!(out, [0.5, -2.0]);
+        let mut out = [9.0; 5];
+        assert_eq!(value.read_number_array_into(&mut out), Ok(3));
+        assert_eq!(out, [0.5, -2.0, 1000.0, 9.0, 9.0]);
+        assert_eq!(
+            JSONValue::load("[]").read_number_array_into(&mut out),
+            Ok(0)
+        );
+
+        assert_eq!(
+            JSONValue::load(r#"[1, "2", 3]"#).read_number_array_into(&mut out),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+        // Elements past the end of the buffer are not read
+        assert_eq!(
+            JSONValue::load(r#"[1, "2"]"#).read_number_array_into(&mut out[..1]),
+            Ok(1)
+        );
+        assert_eq!(
+            JSONValue::load("[1, 2").read_number_array_into(&mut out),
+            Err(JSONParsingError::EndOfStream)
+        );
+        assert_eq!(
+            JSONValue::load("1").read_number_array_into(&mut out),
+            Err(JSONParsingError::CannotParseArray)
+        );
+    }
+
+    #[test]
+    fn raw_text() {
+        assert_eq!(
+            JSONValue::parse("  {\"a\":1} ").unwrap().0.raw(),
+            "{\"a\":1}"
+        );
+        assert_eq!(JSONValue::load(" \"x\" , 2").raw(), "\"x\"");
+        let value = JSONValue::load("[ 1 , {\"b\" : [ ] } ]");
+        let items: std::vec::Vec<_> = value.iter_array().unwrap().map(|item| item.raw()).collect();
+        assert_eq!(items, ["1", "{\"b\" : [ ] }"]);
+        assert_eq!(JSONValue::load("[1, ").raw(), "[1,");
+    }
+
+    #[test]
+    fn spans() {
+        let (value, span) = JSONValue::parse_spanned("  42  ").unwrap();
+        assert_eq!(span, 2..4);
+        assert_eq!(value.read_integer(), Ok(42));
+        let contents = "\u{feff}\n{\"a\": \"b\"}";
+        let (value, span) = JSONValue::parse_spanned(contents).unwrap();
+        assert_eq!(&contents[span], value.raw());
+        assert_eq!(JSONValue::parse_spanned("[]").unwrap().1, 0..2);
+
+        let error = JSONValue::parse_spanned("  [1,").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::EndOfStream);
+        assert_eq!(error.offset, 5);
+    }
+
+    #[test]
+    fn typed_lengths() {
+        let array = JSONValue::load("[1, {\"a\": 2}, [3, 4]]");
+        let object = JSONValue::load("{\"a\": [1, 2], \"b\": {}}");
+        assert_eq!(array.array_len(), Ok(3));
+        assert_eq!(object.object_len(), Ok(2));
+        assert_eq!(array.object_len(), Err(JSONParsingError::CannotParseObject));
+        assert_eq!(object.array_len(), Err(JSONParsingError::CannotParseArray));
+        assert_eq!(JSONValue::load(" [ ] ").array_len(), Ok(0));
+        assert_eq!(JSONValue::load(" { } ").object_len(), Ok(0));
+        assert_eq!(
+            JSONValue::load("\"[1]\"").array_len(),
+            Err(JSONParsingError::CannotParseArray)
+        );
+        assert_eq!(
+            JSONValue::load("{\"a\" 1}").object_len(),
+            Err(JSONParsingError::UnexpectedToken)
+        );
+
+        // Malformed arrays give an error rather than the number of elements before the problem
+        for (contents, error) in [
+            ("[1, x]", JSONParsingError::UnexpectedToken),
+            ("[1 2]", JSONParsingError::UnexpectedToken),
+            ("[1,", JSONParsingError::EndOfStream),
+            ("[", JSONParsingError::EndOfStream),
+        ] {
+            assert_eq!(
+                JSONValue::load(contents).array_len(),
+                Err(error),
+                "{}",
+                contents
+            );
+        }
+        let value = JSONValue::load("[1, x]");
+        assert_eq!(value.iter_array().unwrap().count(), 1);
+        assert_eq!(value.get_nth_array_item(0).unwrap().read_integer(), Ok(1));
+        assert_eq!(
+            value.get_nth_array_item(1).unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+        assert_eq!(
+            JSONValue::load("[1]").get_nth_array_item(1).unwrap_err(),
+            JSONParsingError::IndexOutOfBounds
+        );
+    }
+
+    #[test]
+    fn nth_entries() {
+        let value = JSONValue::load(r#"{"a":1,"b":2}"#);
+        let (key, member) = value.nth_entry(1).unwrap();
+        assert_eq!(key, "b");
+        assert_eq!(member.read_integer(), Ok(2));
+        assert_eq!(value.nth_entry(0).unwrap().0, "a");
+        assert_eq!(
+            value.nth_entry(2).unwrap_err(),
+            JSONParsingError::IndexOutOfBounds
+        );
+        assert_eq!(
+            JSONValue::load("{}").nth_entry(0).unwrap_err(),
+            JSONParsingError::IndexOutOfBounds
+        );
+        assert_eq!(
+            JSONValue::load("[1]").nth_entry(0).unwrap_err(),
+            JSONParsingError::CannotParseObject
+        );
+        assert_eq!(
+            JSONValue::load(r#"{"a": 1, "b" 2}"#)
+                .nth_entry(1)
+                .unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn value_only_parsing() {
+        for contents in ["  42 ", "[1, {\"a\": null}]", "\"x\" \"y\"", "\u{feff}true"] {
+            let value = JSONValue::parse_value(contents).unwrap();
+            let (expected, _) = JSONValue::parse(contents).unwrap();
+            assert_eq!(value.raw(), expected.raw());
+            assert_eq!(value.value_type, expected.value_type);
+        }
+        let error = JSONValue::parse_value("[1,,2]").unwrap_err();
+        assert_eq!(error, JSONValue::parse("[1,,2]").unwrap_err());
+        assert_eq!(error.offset, 3);
+    }
+
+    #[test]
+    fn mapping_arrays() {
+        let value = JSONValue::load(r#"["1","2"]"#);
+        let items: Result<std::vec::Vec<_>, _> =
+            value.map_array(|item| item.read_string_raw()).collect();
+        assert_eq!(items, Ok(std::vec!["1", "2"]));
+        let mut lengths = value.map_array(|item| item.string_decoded_len());
+        assert_eq!(lengths.next(), Some(Ok(1)));
+
+        let mut items = value.map_array(|item| item.read_integer());
+        assert_eq!(
+            items.next(),
+            Some(Err(JSONParsingError::CannotParseInteger))
+        );
+        assert_eq!(
+            items.next(),
+            Some(Err(JSONParsingError::CannotParseInteger))
+        );
+        assert_eq!(items.next(), None);
+
+        let mut items = JSONValue::load(r#"{"a": 1}"#).map_array(|item| item.read_integer());
+        assert_eq!(items.next(), Some(Err(JSONParsingError::CannotParseArray)));
+        assert_eq!(items.next(), None);
+        assert_eq!(JSONValue::load("[]").map_array(Ok).count(), 0);
+    }
+
+    #[test]
+    fn reverse_arrays() {
+        let value = JSONValue::load("[1,2,3]");
+        let items: std::vec::Vec<_> = value
+            .iter_array()
+            .unwrap()
+            .rev()
+            .map(|item| item.read_integer().unwrap())
+            .collect();
+        assert_eq!(items, [3, 2, 1]);
+
+        // Iterating from both ends meets in the middle
+        let value = JSONValue::load(r#" [ "a" , [1, 2] , {"b": 3} , null ] "#);
+        let mut items = value.iter_array().unwrap();
+        assert_eq!(items.next_back().unwrap().value_type, JSONValueType::Null);
+        assert_eq!(items.next().unwrap().read_string(&mut [0; 64]), Ok("a"));
+        assert_eq!(items.next_back().unwrap().value_type, JSONValueType::Object);
+        assert_eq!(items.next().unwrap().len(), Ok(2));
+        assert!(items.next().is_none());
+        assert!(items.next_back().is_none());
+
+        let value = JSONValue::load("[1, 2, 3, 4]");
+        let last_even = value
+            .iter_array()
+            .unwrap()
+            .rfind(|item| item.read_integer().unwrap() % 2 == 0);
+        assert_eq!(last_even.unwrap().read_integer(), Ok(4));
+        assert!(JSONValue::load("[]")
+            .iter_array()
+            .unwrap()
+            .next_back()
+            .is_none());
+    }
+
+    #[test]
+    fn counting_keys() {
+        let value = JSONValue::load(r#"{"x1":1,"x2":2,"y":3}"#);
+        assert_eq!(value.count_keys(|key| key.starts_with('x')), Ok(2));
+        assert_eq!(value.count_keys(|_| true), Ok(3));
+        assert_eq!(value.count_keys(|key| key == "z"), Ok(0));
+        let nested = JSONValue::load(r#"{"x": {"x": 1, "x2": 2}}"#);
+        assert_eq!(nested.count_keys(|key| key.starts_with('x')), Ok(1));
+        assert_eq!(JSONValue::load("{}").count_keys(|_| true), Ok(0));
+
+        // Escaped keys are decoded, up to 256 bytes long
+        let value = JSONValue::load(r#"{"a\u0062": 1, "ab": 2, "\u00e9\n": 3, "a": 4}"#);
+        assert_eq!(value.count_keys(|key| key == "ab"), Ok(2));
+        assert_eq!(value.count_keys(|key| key == "é\n"), Ok(1));
+        let long_key = std::format!(r#"{{"\u0061{}": 1}}"#, "a".repeat(256));
+        assert_eq!(
+            JSONValue::load(&long_key).count_keys(|_| true),
+            Err(JSONParsingError::CannotParseString)
+        );
+        let long_key = std::format!(r#"{{"\u0061{}": 1}}"#, "a".repeat(255));
+        assert_eq!(
+            JSONValue::load(&long_key).count_keys(|key| key.len() == 256),
+            Ok(1)
+        );
+        assert_eq!(
+            JSONValue::load(r#"{"\q": 1}"#).count_keys(|_| true),
+            Err(JSONParsingError::InvalidEscapeSequence('q'))
+        );
+
+        assert_eq!(
+            JSONValue::load(r#"["x"]"#).count_keys(|_| true),
+            Err(JSONParsingError::CannotParseObject)
+        );
+        assert_eq!(
+            JSONValue::load(r#"{"x": 1, "x2"}"#).count_keys(|_| true),
+            Err(JSONParsingError::UnexpectedToken)
+        );
+    }
+
+    #[test]
+    fn remainders() {
+        let (value, rest) = JSONValue::parse_remainder("1 2 3").unwrap();
+        assert_eq!(value.read_integer(), Ok(1));
+        assert_eq!(rest, " 2 3");
+        let (value, rest) = JSONValue::parse_remainder(rest).unwrap();
+        assert_eq!(value.read_integer(), Ok(2));
+        assert_eq!(rest, " 3");
+        assert_eq!(JSONValue::parse_remainder("[1, 2]\n").unwrap().1, "\n");
+        assert_eq!(JSONValue::parse_remainder("\u{feff}{}").unwrap().1, "");
+
+        let error = JSONValue::parse_remainder(" [1 2]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 4);
+    }
+
+    #[test]
+    fn unterminated_containers() {
+        for contents in [
+            "{\"a\":1",
+            "[1,2",
+            "{\"a\":[1,2]",
+            "[{}, {\"b\": 2}",
+            "{",
+            "[",
+            "{\"a\":",
+        ] {
+            let error = JSONValue::parse(contents).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::EndOfStream, "{}", contents);
+            assert_eq!(error.offset, contents.len(), "{}", contents);
+            assert!(JSONValue::load(contents).verify().is_err(), "{}", contents);
+        }
+        for contents in ["{\"a\":1}", "[1,2]", "{\"a\":[1,2]}", "[{}, {\"b\": 2}]"] {
+            let (_, value_len) = JSONValue::parse(contents).unwrap();
+            assert_eq!(value_len, contents.len(), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn empty_elements() {
+        let cases = [
+            ("[1,,2]", 3),
+            ("[,1]", 1),
+            ("[,]", 1),
+            ("[1,]", 3),
+            ("{\"a\":1,,\"b\":2}", 7),
+            ("{,\"a\":1}", 1),
+            ("{\"a\":1,}", 7),
+        ];
+        for (contents, offset) in cases {
+            let error = JSONValue::parse(contents).unwrap_err();
+            assert_eq!(
+                error.kind,
+                JSONParsingError::UnexpectedToken,
+                "{}",
+                contents
+            );
+            assert_eq!(error.offset, offset, "{}", contents);
+        }
+        assert_eq!(
+            JSONValue::load("{\"a\":1,,\"b\":2}")
+                .get_key_value("b")
+                .unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn whitespace_around_colons() {
+        for contents in [
+            "{\"a\"\n:\r 1}",
+            "{\"a\"\r\n:\r\n1\r\n}",
+            "{ \"a\" \t : \t 1 }",
+            "{\"b\":0 ,\n\"a\"\n:\n1}",
+        ] {
+            let value = JSONValue::load_and_verify(contents).unwrap();
+            assert_eq!(
+                value.get_key_value("a").unwrap().read_integer(),
+                Ok(1),
+                "{:?}",
+                contents
+            );
+        }
+        // Only JSON whitespace is skipped in strict mode
+        let error = JSONValue::parse("{\"a\"\u{a0}: 1}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 4);
+        assert_eq!(
+            JSONValue::load("{\"a\"\u{a0}: 1}")
+                .get_key_value("a")
+                .unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn ascii_strings() {
+        assert_eq!(
+            JSONValue::load(r#""hello, world""#).string_is_ascii(),
+            Ok(true)
+        );
+        assert_eq!(JSONValue::load(r#""""#).string_is_ascii(), Ok(true));
+        assert_eq!(JSONValue::load(r#""café""#).string_is_ascii(), Ok(false));
+        assert_eq!(JSONValue::load(r#""\u00e9""#).string_is_ascii(), Ok(false));
+        assert_eq!(
+            JSONValue::load(r#""\u007f\n\"""#).string_is_ascii(),
+            Ok(true)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\ud83d\ude00""#).string_is_ascii(),
+            Ok(false)
+        );
+        assert_eq!(
+            JSONValue::load(r#""é \x""#).string_is_ascii(),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            JSONValue::load("42").string_is_ascii(),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load(r#""open"#).string_is_ascii(),
+            Err(JSONParsingError::EndOfStream)
+        );
+    }
+
+    #[test]
+    fn peeking_types() {
+        let cases = [
+            ("{", JSONValueType::Object),
+            ("[", JSONValueType::Array),
+            ("\"", JSONValueType::String),
+            ("-", JSONValueType::Number),
+            ("0", JSONValueType::Number),
+            ("7", JSONValueType::Number),
+            ("t", JSONValueType::Bool),
+            ("f", JSONValueType::Bool),
+            ("n", JSONValueType::Null),
+        ];
+        for (start, value_type) in cases {
+            assert_eq!(JSONValue::peek_type(start), Ok(value_type), "{}", start);
+        }
+        // Only the first character is looked at
+        assert_eq!(
+            JSONValue::peek_type(" \r\n\t[1, }"),
+            Ok(JSONValueType::Array)
+        );
+        assert_eq!(JSONValue::peek_type("nope"), Ok(JSONValueType::Null));
+
+        for start in [
+            "x", "}", "]", ",", "'", "+1", ".5", "NaN", "Infinity", "\u{a0}1",
+        ] {
+            assert_eq!(
+                JSONValue::peek_type(start),
+                Err(JSONParsingError::UnexpectedToken),
+                "{}",
+                start
+            );
         }
+        assert_eq!(
+            JSONValue::peek_type(" \n"),
+            Err(JSONParsingError::EndOfStream)
+        );
     }
-}
-
-/// An iterator through a JSON array value
-///
-/// Usually constructed with [`JSONValue::iter_array`].
-pub struct JSONArrayIterator<'a> {
-    contents: &'a str,
-}
 
-impl<'a> Iterator for JSONArrayIterator<'a> {
-    type Item = JSONValue<'a>;
+    #[test]
+    fn nested_value_lengths() {
+        for contents in [
+            "{\"a\": {\"b\":1} , \"c\":2}",
+            "{\"a\" : [1, [2]]\n,\n\"b\" : { } }",
+            "{ \"a\" : { \"b\" : { \"c\" : [ ] } } }",
+            "[ {\"a\": [ ]} , [ {} ] ]",
+        ] {
+            let (value, value_len) = JSONValue::parse(contents).unwrap();
+            assert_eq!(value_len, contents.len(), "{}", contents);
+            assert_eq!(value.raw(), contents, "{}", contents);
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match JSONValue::parse_with_len(self.contents) {
-            Ok((value, value_len)) => {
-                self.contents = &self.contents[value_len..].trim_start()[1..];
-                Some(value)
-            }
-            _ => None,
+        // The same lengths are found when the values follow each other in a stream
+        let stream = "{\"a\": {\"b\":1} , \"c\":2} [ [1] , {\"d\": []} ]{\"e\":{}}";
+        let mut rest = stream;
+        let mut values = 0;
+        while !rest.trim().is_empty() {
+            let (value, value_len) = JSONValue::parse(rest).unwrap();
+            assert!(value.verify().is_ok());
+            rest = &rest[value_len..];
+            values += 1;
         }
+        assert_eq!(values, 3);
+        let value = JSONValue::load("{\"a\": {\"b\":1} , \"c\":2}");
+        assert_eq!(value.get_key_value("c").unwrap().read_integer(), Ok(2));
     }
-}
 
-/// Iterator over a JSON-escaped string
-///
-/// See [`JSONValue::iter_string`] for further documentation.
-pub struct EscapedStringIterator<'a> {
-    contents: core::str::Chars<'a>,
-    done: bool,
-}
+    #[test]
+    fn empty_container_elements() {
+        let contents = "[{},{},[]]";
+        let (value, value_len) = JSONValue::parse(contents).unwrap();
+        assert_eq!(value_len, contents.len());
+        assert_eq!(value.len(), Ok(3));
+        let second = value.get_nth_array_item(1).unwrap();
+        assert_eq!(second.value_type, JSONValueType::Object);
+        assert_eq!(second.len(), Ok(0));
+        assert_eq!(second.raw(), "{}");
+        assert_eq!(value.get_nth_array_item(2).unwrap().raw(), "[]");
+        assert!(value.get_nth_array_item(3).is_err());
 
-impl<'a> Iterator for EscapedStringIterator<'a> {
-    type Item = Result<char, JSONParsingError>;
+        let value = JSONValue::load("[ [] , {} ,[[]],{\"a\":{}} ]");
+        let items: std::vec::Vec<_> = value.iter_array().unwrap().map(|item| item.raw()).collect();
+        assert_eq!(items, ["[]", "{}", "[[]]", "{\"a\":{}}"]);
+        let value = JSONValue::load("{\"a\":{},\"b\":[],\"c\":1}");
+        assert_eq!(value.get_key_value("c").unwrap().read_integer(), Ok(1));
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            None
-        } else {
-            let chr = self.contents.next();
-            match chr {
-                Some('\\') => {
-                    let chr = self.contents.next();
-                    match chr {
-                        Some('"' | '\\' | '/') => chr.map(Ok),
-                        Some('b') => Some(Ok('\x08')),
-                        Some('f') => Some(Ok('\x0c')),
-                        Some('n') => Some(Ok('\n')),
-                        Some('t') => Some(Ok('\t')),
-                        Some('r') => Some(Ok('\r')),
-                        Some('u') => {
-                            let mut get_digit = || {
-                                self.contents
-                                    .next()
-                                    .and_then(|x| x.to_digit(16))
-                                    .ok_or(JSONParsingError::TooShortEscapeSequence)
-                            };
-                            let mut parse_unicode = || {
-                                let code = [get_digit()?, get_digit()?, get_digit()?, get_digit()?];
-                                let code =
-                                    (code[0] << 12) | (code[1] << 8) | (code[2] << 4) | code[3];
-                                char::from_u32(code)
-                                    .ok_or(JSONParsingError::InvalidUnicodeEscapeSequence)
-                            };
-                            match parse_unicode() {
-                                Ok(chr) => Some(Ok(chr)),
-                                Err(e) => {
-                                    self.done = true;
-                                    Some(Err(e))
-                                }
-                            }
-                        }
-                        Some(x) => {
-                            self.done = true;
-                            Some(Err(JSONParsingError::InvalidEscapeSequence(x)))
-                        }
-                        None => None,
-                    }
-                }
-                Some('"') => {
-                    self.done = true;
-                    None
-                }
-                None => {
-                    self.done = true;
-                    Some(Err(JSONParsingError::EndOfStream))
-                }
-                _ => chr.map(Ok),
-            }
+        for contents in ["[{},]", "[{}{}]", "[[],,[]]", "[{},[]"] {
+            assert!(JSONValue::parse(contents).is_err(), "{}", contents);
         }
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    extern crate std;
 
     #[test]
-    fn integer() {
-        let (value, value_len) = JSONValue::parse_with_len("42").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Number);
-        assert_eq!(value_len, 2);
-        assert_eq!(value.read_integer(), Ok(42));
-        assert!(value.read_string().is_err());
+    fn skipping_values() {
+        let contents = "  {\"a\":1} ";
+        assert_eq!(
+            JSONValue::skip_value(contents),
+            Ok(JSONValue::parse(contents).unwrap().1)
+        );
+        assert_eq!(JSONValue::skip_value(contents), Ok(9));
+        assert_eq!(JSONValue::skip_value("\u{feff}true false"), Ok(7));
 
-        assert_eq!(JSONValue::load("-98").read_integer(), Ok(-98));
-        assert_eq!(JSONValue::load("-99 ").read_integer(), Ok(-99));
+        let error = JSONValue::skip_value("[1, {\"a\" 2}]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 9);
     }
 
     #[test]
-    fn float() {
-        let (value, value_len) = JSONValue::parse_with_len("3.141592").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Number);
-        assert_eq!(value_len, "3.141592".len());
+    fn missing_separators() {
+        // Missing colon
+        let error = JSONValue::parse("{\"a\" 1}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 5);
+        // Missing comma
+        let error = JSONValue::parse("{\"a\":1 \"b\":2}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 7);
+        let error = JSONValue::parse("[1 2]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 3);
+
+        // Iterating reports the problem rather than stopping early
+        let value = JSONValue::load("{\"a\":1 \"b\":2}");
+        let mut members = value.iter_object().unwrap();
+        assert_eq!(members.next().unwrap().unwrap().0, "a");
         assert_eq!(
-            value.read_integer(),
-            Err(JSONParsingError::CannotParseInteger)
+            members.next().unwrap().unwrap_err(),
+            JSONParsingError::UnexpectedToken
         );
+        assert!(members.next().is_none());
         assert_eq!(
-            value.read_string(),
-            Err(JSONParsingError::CannotParseString)
+            value.get_key_value("b").unwrap_err(),
+            JSONParsingError::UnexpectedToken
         );
-        assert!((value.read_float().unwrap() - 3.141592).abs() < 0.0001);
-
         assert_eq!(
-            JSONValue::load("-3.43w").read_float(),
-            Err(JSONParsingError::CannotParseFloat)
+            JSONValue::load("{\"a\" 1}").get_key_value("a").unwrap_err(),
+            JSONParsingError::UnexpectedToken
         );
     }
 
     #[test]
-    fn string() {
-        let (value, value_len) = JSONValue::parse_with_len("\"hello world\"").unwrap();
-        assert_eq!(value.value_type, JSONValueType::String);
-        assert_eq!(value_len, "\"hello world\"".len());
-        assert!(value.read_integer().is_err());
-        assert_eq!(value.read_string(), Ok("hello world"));
+    fn single_characters() {
+        assert_eq!(JSONValue::load(r#""W""#).read_char(), Ok('W'));
+        assert_eq!(JSONValue::load(r#""\u0041""#).read_char(), Ok('A'));
+        assert_eq!(JSONValue::load(r#""\n""#).read_char(), Ok('\n'));
+        assert_eq!(JSONValue::load(r#""é""#).read_char(), Ok('é'));
+        assert_eq!(
+            JSONValue::load(r#""\ud83d\ude00""#).read_char(),
+            Ok('\u{1f600}')
+        );
 
-        let value = JSONValue::load("\"hello world\"   ");
-        assert_eq!(value.read_string(), Ok("hello world"));
+        assert_eq!(
+            JSONValue::load(r#""""#).read_char(),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load(r#""NE""#).read_char(),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\u0041\u0042""#).read_char(),
+            Err(JSONParsingError::CannotParseString)
+        );
+        assert_eq!(
+            JSONValue::load(r#""\x""#).read_char(),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            JSONValue::load(r#""A\x""#).read_char(),
+            Err(JSONParsingError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            JSONValue::load("65").read_char(),
+            Err(JSONParsingError::CannotParseString)
+        );
     }
 
     #[test]
-    fn array() {
-        let (value, value_len) = JSONValue::parse_with_len("[1,2,3]").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Array);
-        assert_eq!(value_len, "[1,2,3]".len());
-        let (value, value_len) = JSONValue::parse_with_len("[]").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Array);
-        assert_eq!(value_len, "[]".len());
-        let (value, value_len) = JSONValue::parse_with_len("  [\n  ]").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Array);
-        assert_eq!(value_len, "  [\n  ]".len());
-        let (value, value_len) = JSONValue::parse_with_len("[1  ,  2\t,\r3\n]").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Array);
-        assert_eq!(value_len, "[1  ,  2\t,\r3\n]".len());
-
-        assert!(value.read_integer().is_err());
-        assert!(value.read_string().is_err());
+    fn utf16_lengths() {
+        assert_eq!(JSONValue::load(r#""hello""#).string_utf16_len(), Ok(5));
+        assert_eq!(JSONValue::load(r#""""#).string_utf16_len(), Ok(0));
+        assert_eq!(JSONValue::load(r#""é""#).string_utf16_len(), Ok(1));
+        assert_eq!(JSONValue::load(r#""caf\u00e9""#).string_utf16_len(), Ok(4));
+        assert_eq!(JSONValue::load(r#""😀""#).string_utf16_len(), Ok(2));
         assert_eq!(
-            value.iter_array().unwrap().nth(0).unwrap().read_integer(),
-            Ok(1)
+            JSONValue::load(r#""a\ud83d\ude00b""#).string_utf16_len(),
+            Ok(4)
         );
         assert_eq!(
-            value.iter_array().unwrap().nth(1).unwrap().read_integer(),
-            Ok(2)
+            JSONValue::load(r#""\ud83d""#).string_utf16_len(),
+            Err(JSONParsingError::InvalidUnicodeEscapeSequence)
         );
         assert_eq!(
-            value.iter_array().unwrap().nth(2).unwrap().read_integer(),
-            Ok(3)
+            JSONValue::load("[]").string_utf16_len(),
+            Err(JSONParsingError::CannotParseString)
         );
     }
 
     #[test]
-    fn object() {
-        let input = "{
-        \"id\": 0,
-        \"name\": \"Ginger Fuller\"}";
-        let (value, value_len) = JSONValue::parse_with_len(input).unwrap();
-        assert_eq!(value.value_type, JSONValueType::Object);
-        assert_eq!(value_len, input.len());
-
-        assert!(value.read_integer().is_err());
-        assert!(value.read_string().is_err());
-        assert_eq!(value.get_key_value("id").unwrap().read_integer(), Ok(0));
+    fn folding_arrays() {
+        let value = JSONValue::load("[1,2,3]");
         assert_eq!(
-            value.get_key_value("name").unwrap().read_string(),
-            Ok("Ginger Fuller")
+            value.fold_array(0i64, |sum, item| Ok(sum + item.read_i64()?)),
+            Ok(6)
         );
         assert_eq!(
-            value.get_key_value("surname").err(),
-            Some(JSONParsingError::KeyNotFound)
+            value.fold_array(None, |_, item| Ok(Some(item.raw()))),
+            Ok(Some("3"))
         );
+        assert_eq!(JSONValue::load("[]").fold_array(7, |_, _| Ok(0)), Ok(7));
 
-        assert!(JSONValue::load("{\"foo\":[{}]}").value_type != JSONValueType::Error);
-        assert!(JSONValue::load("[{\"foo\":{}}]").value_type != JSONValueType::Error);
-    }
-    #[test]
-
-    fn this_broke_once() {
-        assert!(
-            JSONValue::load(
-                r##"
-[{"a":{"email":"d@"},"m":"#20\n\n.\n"}]
-    "##
-            )
-            .value_type
-                != JSONValueType::Error
-        )
+        assert_eq!(
+            JSONValue::load("[1, \"2\"]").fold_array(0, |sum, item| Ok(sum + item.read_i64()?)),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert_eq!(
+            JSONValue::load("[1, 2").fold_array(0, |sum, _| Ok(sum + 1)),
+            Err(JSONParsingError::EndOfStream)
+        );
+        assert_eq!(
+            JSONValue::load("{}").fold_array(0, |sum, _| Ok(sum + 1)),
+            Err(JSONParsingError::CannotParseArray)
+        );
     }
 
     #[test]
-    fn integer_whitespace() {
-        let (value, value_len) = JSONValue::parse_with_len("  42	").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Number);
-        assert_eq!(value_len, "  42".len());
-        let (value, value_len) = JSONValue::parse_with_len("\n 42\r").unwrap();
-        assert_eq!(value.value_type, JSONValueType::Number);
-        assert_eq!(value_len, "\n 42".len());
-    }
+    fn defaults() {
+        let value = JSONValue::load(r#"{"present": "stored", "null": null}"#);
+        let default = || JSONValue::load(r#""default""#);
+        assert_eq!(
+            value
+                .get_or_else("present", default)
+                .read_string(&mut [0; 64]),
+            Ok("stored")
+        );
+        assert_eq!(
+            value
+                .get_or_else("missing", default)
+                .read_string(&mut [0; 64]),
+            Ok("default")
+        );
+        assert!(value.get_or_else("null", default).is_null());
+        assert_eq!(
+            JSONValue::load("[1]")
+                .get_or_else("present", default)
+                .read_string(&mut [0; 64]),
+            Ok("default")
+        );
 
-    #[test]
-    fn string_whitespace() {
-        let (value, value_len) = JSONValue::parse_with_len("  \"foo me a bar\"	").unwrap();
-        assert_eq!(value.value_type, JSONValueType::String);
-        assert_eq!(value_len, "  \"foo me a bar\"".len());
-        let (value, value_len) = JSONValue::parse_with_len("\n \"a bar\n I said.\"\r").unwrap();
-        assert_eq!(value.value_type, JSONValueType::String);
-        assert_eq!(value_len, "\n \"a bar\n I said.\"".len());
+        // The default is only computed when needed
+        let mut calls = 0;
+        value.get_or_else("present", || {
+            calls += 1;
+            default()
+        });
+        assert_eq!(calls, 0);
     }
 
     #[test]
-    fn peeking_value_type() {
-        assert_eq!(JSONValue::peek_value_type("123"), JSONValueType::Number);
-        assert_eq!(JSONValue::peek_value_type("12.3"), JSONValueType::Number);
-        assert_eq!(JSONValue::peek_value_type("12.3e10"), JSONValueType::Number);
-        assert_eq!(JSONValue::peek_value_type("-3"), JSONValueType::Number);
-        assert_eq!(JSONValue::peek_value_type("-3.5"), JSONValueType::Number);
-        assert_eq!(JSONValue::peek_value_type("null"), JSONValueType::Null);
-        assert_eq!(JSONValue::peek_value_type("true"), JSONValueType::Bool);
-        assert_eq!(JSONValue::peek_value_type("false"), JSONValueType::Bool);
-        assert_eq!(JSONValue::peek_value_type("[]"), JSONValueType::Array);
-        assert_eq!(JSONValue::peek_value_type("[12]"), JSONValueType::Array);
-        assert_eq!(JSONValue::peek_value_type("[1,2]"), JSONValueType::Array);
-        assert_eq!(JSONValue::peek_value_type("[[]]"), JSONValueType::Array);
-        assert_eq!(JSONValue::peek_value_type("\"foo\""), JSONValueType::String);
-        assert_eq!(JSONValue::peek_value_type("{}"), JSONValueType::Object);
-        assert_eq!(
-            JSONValue::peek_value_type("{\"a\":2}"),
-            JSONValueType::Object
+    fn lone_surrogates() {
+        assert!(JSONValue::is_valid(r#""\uD834\uDD1E""#));
+        assert!(JSONValue::is_valid(r#"{"\ud834\udd1e": ["\ud834\udd1e"]}"#));
+        for contents in [
+            r#""\uD834""#,
+            r#""\uDD1E""#,
+            r#""\uD834 \uDD1E""#,
+            r#""\uD834\u0041""#,
+            r#""\uDD1E\uD834""#,
+            r#"{"\ud834": 1}"#,
+            r#"["ok", "\udd1e"]"#,
+        ] {
+            assert!(!JSONValue::is_valid(contents), "{}", contents);
+        }
+
+        // Validation agrees with decoding the string, and points at the escape
+        let contents = r#"["ok", "lone \uD834!"]"#;
+        let error = JSONValue::parse(contents).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidUnicodeEscapeSequence);
+        assert_eq!(error.offset, 13);
+        let string = JSONValue::load(&contents[7..]);
+        assert_eq!(
+            string.iter_string().unwrap().find_map(Result::err),
+            Some(error.kind)
         );
-        assert_eq!(JSONValue::peek_value_type("<"), JSONValueType::Error);
-        assert_eq!(JSONValue::peek_value_type("bar"), JSONValueType::Error);
+        let error = JSONValue::parse(r#""bad \x escape""#).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::InvalidEscapeSequence('x'));
+        assert_eq!(error.offset, 5);
     }
 
     #[test]
-    fn verifying() {
-        assert!(JSONValue::load_and_verify(" 123 ").is_ok());
-        assert!(JSONValue::load_and_verify("[123]").is_ok());
-        assert!(JSONValue::load_and_verify("\"foo\"").is_ok());
+    fn indexed_arrays() {
+        let value = JSONValue::load(r#"["a","b"]"#);
+        let items: std::vec::Vec<_> = value
+            .array_iter_indexed()
+            .unwrap()
+            .map(|(index, item)| (index, item.read_string_raw().unwrap()))
+            .collect();
+        assert_eq!(items, [(0, "a"), (1, "b")]);
+        assert_eq!(
+            JSONValue::load("[]").array_iter_indexed().unwrap().count(),
+            0
+        );
+        assert!(JSONValue::load(r#"{"a": 1}"#).array_iter_indexed().is_err());
     }
 
     #[test]
-    fn string_iterator() {
-        let try_parse_string = |s| {
-            JSONValue::load(s)
-                .iter_string()
+    fn trailing_whitespace() {
+        assert_eq!(JSONValue::parse("42   ").unwrap().1, 2);
+        assert_eq!(
+            JSONValue::parse_consuming_trailing_ws("42   ").unwrap().1,
+            5
+        );
+        assert_eq!(JSONValue::parse_consuming_trailing_ws("42").unwrap().1, 2);
+        let contents = " [1] \r\n\t{}";
+        let (value, value_len) = JSONValue::parse_consuming_trailing_ws(contents).unwrap();
+        assert_eq!(value.raw(), "[1]");
+        assert_eq!(&contents[value_len..], "{}");
+        // Only JSON whitespace is consumed
+        assert_eq!(
+            JSONValue::parse_consuming_trailing_ws("1 \u{a0}")
                 .unwrap()
-                .collect::<Result<std::string::String, _>>()
-        };
-        let value = try_parse_string("\"I have a dream\"").unwrap();
-        assert_eq!(value, "I have a dream");
-
-        let value = try_parse_string("\"\\\"I have a dream\\\"\"").unwrap();
-        assert_eq!(value, "\"I have a dream\"");
-
-        let value = try_parse_string(r#" "\"I\n\thave\b\fa\\dream\/\"\u00a3" "#).unwrap();
-        assert_eq!(value, "\"I\n\thave\x08\x0ca\\dream/\"£");
+                .1,
+            2
+        );
 
-        let value = try_parse_string(r#" " "#);
-        assert!(matches!(value, Err(JSONParsingError::EndOfStream)));
-        let value = try_parse_string(r#" "foo\" "#);
-        assert!(matches!(value, Err(JSONParsingError::EndOfStream)));
-        let value = try_parse_string(r#" "Odd escape: \?" "#);
-        assert!(matches!(
-            value,
-            Err(JSONParsingError::InvalidEscapeSequence('?'))
-        ));
-        let value = try_parse_string(r#" "\uwxyz" "#);
-        assert!(matches!(
-            value,
-            Err(JSONParsingError::TooShortEscapeSequence)
-        ));
-        let value = try_parse_string(r#" "\u012" "#);
-        assert!(matches!(
-            value,
-            Err(JSONParsingError::TooShortEscapeSequence)
-        ));
-        // This is not a single character codepoint under utf-16
-        let value = try_parse_string(r#" "\ud834" "#);
-        assert!(matches!(
-            value,
-            Err(JSONParsingError::InvalidUnicodeEscapeSequence)
-        ));
+        let error = JSONValue::parse_consuming_trailing_ws("[1 x]   ").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 3);
     }
 
     #[test]
-    fn object_iterator() {
-        let json_value = JSONValue::load("{\"foo\" : [], \"bar\":{\"baz\": 2}}");
-        let keys = ["foo", "bar"];
-        for (item, expected_key) in json_value.iter_object().unwrap().zip(&keys) {
-            assert_eq!(item.unwrap().0, *expected_key);
+    fn lenient_booleans() {
+        let accepted = [
+            ("true", true),
+            ("false", false),
+            ("1", true),
+            ("0", false),
+            ("1.0", true),
+            ("-0", false),
+            ("0e5", false),
+            (r#""true""#, true),
+            (r#""FALSE""#, false),
+            (r#""TrUe""#, true),
+            (r#""\u0074rue""#, true),
+        ];
+        for (contents, expected) in accepted {
+            assert_eq!(
+                JSONValue::load(contents).read_bool_lenient(),
+                Ok(expected),
+                "{}",
+                contents
+            );
+        }
+        for contents in [
+            "2",
+            "-1",
+            "0.5",
+            "1.0000000000000001",
+            r#""yes""#,
+            r#""1""#,
+            r#"" true""#,
+            "null",
+            "[true]",
+        ] {
+            assert_eq!(
+                JSONValue::load(contents).read_bool_lenient(),
+                Err(JSONParsingError::CannotParseBool),
+                "{}",
+                contents
+            );
         }
+        assert_eq!(
+            JSONValue::load("1").read_bool(),
+            Err(JSONParsingError::CannotParseBool)
+        );
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
-    fn string_borrow_past_lifetime_of_value() {
-        let s = "\"abc\"";
-        let t: &str;
-        {
-            t = JSONValue::load(s).read_string().unwrap();
-        }
-        assert_eq!(t, &s[1..s.len()-1]);
+    fn sorted_entries() {
+        let value = JSONValue::load(r#"{"b":1,"a":2}"#);
+        let keys: std::vec::Vec<_> = value
+            .entries_sorted()
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, ["a", "b"]);
+
+        // Keys are decoded, then compared by their UTF-8 bytes, and ties keep document order
+        let value = JSONValue::load(
+            r#"{"é": 0, "z": 1, "\u0061b": 2, "a": 3, "B": 4, "": 5, "z": 6, "😀": 7, "\uffff": 8}"#,
+        );
+        let values: std::vec::Vec<_> = value
+            .entries_sorted()
+            .unwrap()
+            .into_iter()
+            .map(|(_, value)| value.read_integer().unwrap())
+            .collect();
+        assert_eq!(values, [5, 4, 3, 2, 1, 6, 0, 8, 7]);
+        assert_eq!(value.entries_sorted().unwrap()[3].0, "ab");
+        assert!(JSONValue::load("{}").entries_sorted().unwrap().is_empty());
+        let value = Parser::lenient().load("{b: 1, 'a\\'': 2}");
+        let keys: std::vec::Vec<_> = value
+            .entries_sorted()
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, ["a'", "b"]);
+
+        assert_eq!(
+            JSONValue::load("[]").entries_sorted().unwrap_err(),
+            JSONParsingError::CannotParseObject
+        );
+        assert_eq!(
+            JSONValue::load(r#"{"a": 1,"#).entries_sorted().unwrap_err(),
+            JSONParsingError::EndOfStream
+        );
+        assert_eq!(
+            JSONValue::load(r#"{"\q": 1}"#)
+                .entries_sorted()
+                .unwrap_err(),
+            JSONParsingError::InvalidEscapeSequence('q')
+        );
     }
 }
@@ -0,0 +1,937 @@
+use crate::{
+    decode_key, trim_start, JSONLocatedError, JSONParsingError, JSONStreamIterator, JSONValue,
+    Tokens,
+};
+
+/// The default limit on how deeply arrays and objects may be nested
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// The deepest nesting of arrays and objects that can be parsed, whatever the [`Parser`] options
+///
+/// Deeper documents can be parsed by giving the parser a larger stack, with
+/// [`Parser::parse_with_stack`].
+pub const MAX_NESTING_DEPTH: usize = 1024;
+
+/// Options controlling how JSON is parsed
+///
+/// [`Parser::new`] gives the behaviour of [`JSONValue::load`] and friends, and the options can
+/// be adjusted with the builder methods.  A [`JSONValue`] remembers the parser that loaded it, so
+/// nested values are parsed with the same options when they are accessed.
+///
+/// ### Example
+/// ```
+/// # use microjson::{JSONParsingError, Parser};
+/// let parser = Parser::new().max_depth(2);
+/// assert!(parser.parse("[[1]]").is_ok());
+/// assert_eq!(
+///     parser.parse("[[[1]]]").unwrap_err().kind,
+///     JSONParsingError::DepthLimitExceeded
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Parser {
+    pub(crate) max_depth: usize,
+    pub(crate) deny_duplicate_keys: bool,
+    pub(crate) allow_unicode_whitespace: bool,
+    pub(crate) allow_lenient_numbers: bool,
+    pub(crate) allow_nan_and_infinity: bool,
+    pub(crate) allow_comments: bool,
+    pub(crate) allow_trailing_commas: bool,
+    pub(crate) allow_single_quotes: bool,
+    pub(crate) allow_unquoted_keys: bool,
+}
+
+impl Parser {
+    /// A parser with the default options
+    pub const fn new() -> Parser {
+        Parser {
+            max_depth: DEFAULT_MAX_DEPTH,
+            deny_duplicate_keys: false,
+            allow_unicode_whitespace: false,
+            allow_lenient_numbers: false,
+            allow_nan_and_infinity: false,
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+        }
+    }
+
+    /// A parser accepting all of the extensions to JSON commonly found in configuration files
+    ///
+    /// This allows comments, trailing commas, single-quoted strings, unquoted keys, lenient
+    /// numbers, `NaN` and `Infinity`, and any Unicode whitespace, much like JSON5.  Each can still
+    /// be turned off with the builder methods.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::Parser;
+    /// let value = Parser::lenient()
+    ///     .load_and_verify("{name: 'bob', /* age: 42, */ tags: ['a', 'b',],}")
+    ///     .unwrap();
+    /// assert_eq!(value.get_key_value("name").unwrap().read_string(&mut [0; 64]), Ok("bob"));
+    /// assert_eq!(value.len(), Ok(2));
+    /// ```
+    pub const fn lenient() -> Parser {
+        Parser::new()
+            .allow_unicode_whitespace(true)
+            .allow_lenient_numbers(true)
+            .allow_nan_and_infinity(true)
+            .allow_comments(true)
+            .allow_trailing_commas(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+    }
+
+    /// Limit how deeply arrays and objects may be nested
+    ///
+    /// Exceeding the limit results in a [`JSONParsingError::DepthLimitExceeded`] error, rather than
+    /// risking overflowing the stack.  The depth is counted from the value being parsed, so a
+    /// limit of `1` allows `[1, 2]` but not `[[1], 2]`.  Defaults to [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// The parser tracks the enclosing arrays and objects in a fixed-size stack rather than by
+    /// recursing, so limits above [`MAX_NESTING_DEPTH`] are treated as [`MAX_NESTING_DEPTH`].  To
+    /// parse more deeply nested documents, provide a larger stack with
+    /// [`Parser::parse_with_stack`].
+    ///
+    /// [`JSONParsingError::DepthLimitExceeded`]: crate::JSONParsingError::DepthLimitExceeded
+    pub const fn max_depth(mut self, max_depth: usize) -> Parser {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Accept any Unicode whitespace between tokens, rather than only what JSON allows
+    ///
+    /// JSON only allows spaces, tabs, line feeds and carriage returns between tokens.  With this
+    /// enabled, characters such as no-break spaces and vertical tabs are skipped as well.  Off by
+    /// default.
+    pub const fn allow_unicode_whitespace(mut self, allow_unicode_whitespace: bool) -> Parser {
+        self.allow_unicode_whitespace = allow_unicode_whitespace;
+        self
+    }
+
+    /// Accept numbers with a leading `+`, or a leading `.` standing for `0.`
+    ///
+    /// Numbers such as `+3` and `.5` are not valid JSON, but are produced by some devices.  With
+    /// this enabled they are parsed, and read by the number accessors, as `3` and `0.5`.  Off by
+    /// default.
+    pub const fn allow_lenient_numbers(mut self, allow_lenient_numbers: bool) -> Parser {
+        self.allow_lenient_numbers = allow_lenient_numbers;
+        self
+    }
+
+    /// Accept `NaN`, `Infinity` and `-Infinity` as numbers
+    ///
+    /// These are not valid JSON, but are written by some encoders for floating point values
+    /// without a JSON representation, such as Python's `json.dumps`.  They are read by the float
+    /// accessors as the corresponding values.  Off by default.
+    pub const fn allow_nan_and_infinity(mut self, allow_nan_and_infinity: bool) -> Parser {
+        self.allow_nan_and_infinity = allow_nan_and_infinity;
+        self
+    }
+
+    /// Accept `//` line comments and `/* */` block comments wherever whitespace is allowed
+    ///
+    /// Comments are not part of JSON, but are common in hand-written configuration files.  Off
+    /// by default.
+    pub const fn allow_comments(mut self, allow_comments: bool) -> Parser {
+        self.allow_comments = allow_comments;
+        self
+    }
+
+    /// Accept a comma after the last element of an array or the last member of an object
+    ///
+    /// For example, `[1, 2, 3,]` and `{"a": 1,}`.  A comma on its own, as in `[,]`, is still
+    /// rejected.  Off by default.
+    pub const fn allow_trailing_commas(mut self, allow_trailing_commas: bool) -> Parser {
+        self.allow_trailing_commas = allow_trailing_commas;
+        self
+    }
+
+    /// Accept strings and object keys in single quotes, such as `{'name': 'bob'}`
+    ///
+    /// A single-quoted string ends at the next unescaped `'`, so may contain `"` as it is.  It may
+    /// also contain `\'` as an escape sequence for `'`.  Off by default.
+    pub const fn allow_single_quotes(mut self, allow_single_quotes: bool) -> Parser {
+        self.allow_single_quotes = allow_single_quotes;
+        self
+    }
+
+    /// Accept object keys written as identifiers without quotes, such as `{name: "bob"}`
+    ///
+    /// Identifiers are made up of letters, digits, `_` and `$`, but may not start with a digit.
+    /// Off by default.
+    pub const fn allow_unquoted_keys(mut self, allow_unquoted_keys: bool) -> Parser {
+        self.allow_unquoted_keys = allow_unquoted_keys;
+        self
+    }
+
+    /// Reject objects in which the same key appears more than once
+    ///
+    /// Keys are compared after escape sequences are decoded, so `"a"` and `"\u0061"` are the
+    /// same key.  Only keys of the same object are compared, so `[{"a": 1}, {"a": 2}]` is
+    /// accepted.  Duplicates result in a [`JSONParsingError::DuplicateKey`] error at the offset
+    /// of the repeated key.  Off by default, as the check takes time quadratic in the number of
+    /// keys in an object.
+    pub const fn deny_duplicate_keys(mut self, deny_duplicate_keys: bool) -> Parser {
+        self.deny_duplicate_keys = deny_duplicate_keys;
+        self
+    }
+
+    /// Create a new `JSONValue` from an input string using this parser's options
+    ///
+    /// See [`JSONValue::load`].
+    pub fn load<'a>(&self, contents: &'a str) -> JSONValue<'a> {
+        let (contents, _) = trim_start(strip_bom(contents).0, *self);
+        let value_type = JSONValue::peek_value_type(contents, *self);
+        JSONValue {
+            contents,
+            value_type,
+            parser: *self,
+        }
+    }
+
+    /// Load a JSON value from a payload using this parser's options, and verify that it is valid
+    ///
+    /// See [`JSONValue::load_and_verify`].
+    pub fn load_and_verify<'a>(
+        &self,
+        contents: &'a str,
+    ) -> Result<JSONValue<'a>, JSONLocatedError> {
+        let value = self.load(contents);
+        // Errors are relative to the value, which starts after any mark and whitespace
+        let start = value.contents.as_ptr() as usize - contents.as_ptr() as usize;
+        value.verify().map_err(|error| error.shifted(start))?;
+        Ok(value)
+    }
+
+    /// Parse a single value from the start of `contents` using this parser's options
+    ///
+    /// See [`JSONValue::parse`].
+    pub fn parse<'a>(&self, contents: &'a str) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        let (contents, bom_len) = strip_bom(contents);
+        match JSONValue::parse_with_len(contents, *self) {
+            Ok((value, value_len)) => Ok((value, bom_len + value_len)),
+            Err(error) => Err(error.shifted(bom_len)),
+        }
+    }
+
+    /// Parse a single value from the start of `contents`, using `stack` to track nesting
+    ///
+    /// This is [`Parser::parse`], except that the enclosing arrays and objects are recorded in
+    /// `stack` instead of a stack of [`MAX_NESTING_DEPTH`] levels.  Each byte of `stack` holds
+    /// eight levels, so documents nested up to `8 * stack.len()` deep can be parsed without
+    /// recursion, as long as the [`Parser::max_depth`] limit is raised to match.
+    ///
+    /// Only this call uses `stack`.  The returned value's accessors, including
+    /// [`JSONValue::verify`], [`JSONValue::len`] and the iterators, parse the values inside it
+    /// with the usual stack of [`MAX_NESTING_DEPTH`] levels.  So if the value is nested more deeply
+    /// than that, they return `Err(JSONParsingError::DepthLimitExceeded)`, and
+    /// [`JSONValue::iter_array`] stops at the first element which is too deep.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONParsingError, Parser};
+    /// let nested = "[".repeat(5000) + &"]".repeat(5000);
+    /// let parser = Parser::new().max_depth(usize::MAX);
+    /// let error = parser.parse(&nested).unwrap_err();
+    /// assert_eq!(error.kind, JSONParsingError::DepthLimitExceeded);
+    ///
+    /// let (_, value_len) = parser.parse_with_stack(&nested, &mut [0; 5000 / 8]).unwrap();
+    /// assert_eq!(value_len, 10000);
+    /// ```
+    pub fn parse_with_stack<'a>(
+        &self,
+        contents: &'a str,
+        stack: &mut [u8],
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        let (contents, bom_len) = strip_bom(contents);
+        match JSONValue::parse_with_stack(contents, *self, stack) {
+            Ok((value, value_len)) => Ok((value, bom_len + value_len)),
+            Err(error) => Err(error.shifted(bom_len)),
+        }
+    }
+
+    /// Parse a single value from the start of `contents` using this parser's options, consuming
+    /// the whitespace after it too
+    ///
+    /// Comments after the value are consumed as well if the parser allows them.  See
+    /// [`JSONValue::parse_consuming_trailing_ws`].
+    pub fn parse_consuming_trailing_ws<'a>(
+        &self,
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        let (value, value_len) = self.parse(contents)?;
+        let (_, whitespace_trimmed) = trim_start(&contents[value_len..], *self);
+        Ok((value, value_len + whitespace_trimmed))
+    }
+
+    /// Checks the value at the start of `contents` using this parser's options, returning only
+    /// the number of bytes consumed
+    ///
+    /// See [`JSONValue::skip_value`].
+    pub fn skip_value(&self, contents: &str) -> Result<usize, JSONLocatedError> {
+        self.parse(contents).map(|(_, value_len)| value_len)
+    }
+
+    /// Parse a single value from the start of `contents` using this parser's options, without
+    /// the number of bytes consumed
+    ///
+    /// See [`JSONValue::parse_value`].
+    pub fn parse_value<'a>(&self, contents: &'a str) -> Result<JSONValue<'a>, JSONLocatedError> {
+        self.parse(contents).map(|(value, _)| value)
+    }
+
+    /// Parse a single value starting `offset` bytes into `contents` using this parser's options
+    ///
+    /// See [`JSONValue::parse_at`].
+    pub fn parse_at<'a>(
+        &self,
+        contents: &'a str,
+        offset: usize,
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        if offset == 0 {
+            return self.parse(contents);
+        }
+        let rest = contents.get(offset..).ok_or(JSONLocatedError {
+            kind: if offset > contents.len() {
+                JSONParsingError::EndOfStream
+            } else {
+                JSONParsingError::UnexpectedToken
+            },
+            offset: offset.min(contents.len()),
+        })?;
+        match JSONValue::parse_with_len(rest, *self) {
+            Ok((value, value_len)) => Ok((value, offset + value_len)),
+            Err(error) => Err(error.shifted(offset)),
+        }
+    }
+
+    /// Parse a single value from the start of `bytes` using this parser's options
+    ///
+    /// See [`JSONValue::parse_bytes`].
+    pub fn parse_bytes<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> Result<(JSONValue<'a>, usize), JSONLocatedError> {
+        let contents = core::str::from_utf8(bytes).map_err(|error| JSONLocatedError {
+            kind: JSONParsingError::InvalidUtf8,
+            offset: error.valid_up_to(),
+        })?;
+        self.parse(contents)
+    }
+
+    /// Whether `contents` is a single well-formed value according to this parser's options
+    ///
+    /// See [`JSONValue::is_valid`].
+    pub fn is_valid(&self, contents: &str) -> bool {
+        self.parse_exact(contents).is_ok()
+    }
+
+    /// Constructs an iterator over a sequence of values using this parser's options
+    ///
+    /// See [`JSONValue::parse_stream`].
+    pub fn parse_stream<'a>(&self, contents: &'a str) -> JSONStreamIterator<'a> {
+        let (contents, bom_len) = strip_bom(contents);
+        JSONStreamIterator {
+            contents,
+            offset: bom_len,
+            parser: *self,
+        }
+    }
+
+    /// Parse a single value from the start of `contents` using this parser's options, along with
+    /// the bytes it occupies
+    ///
+    /// See [`JSONValue::parse_spanned`].
+    pub fn parse_spanned<'a>(
+        &self,
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, core::ops::Range<usize>), JSONLocatedError> {
+        let (value, value_len) = self.parse(contents)?;
+        Ok((value, value_len - value.contents.len()..value_len))
+    }
+
+    /// Parse a single value from the start of `contents` using this parser's options, along with
+    /// the input following it
+    ///
+    /// See [`JSONValue::parse_remainder`].
+    pub fn parse_remainder<'a>(
+        &self,
+        contents: &'a str,
+    ) -> Result<(JSONValue<'a>, &'a str), JSONLocatedError> {
+        let (value, value_len) = self.parse(contents)?;
+        Ok((value, &contents[value_len..]))
+    }
+
+    /// Parse `contents` as a complete JSON document using this parser's options
+    ///
+    /// See [`JSONValue::parse_exact`].
+    pub fn parse_exact<'a>(&self, contents: &'a str) -> Result<JSONValue<'a>, JSONLocatedError> {
+        let (value, value_len) = self.parse(contents)?;
+        let (rest, whitespace_trimmed) = trim_start(&contents[value_len..], *self);
+        if rest.is_empty() {
+            Ok(value)
+        } else {
+            Err(JSONLocatedError {
+                kind: JSONParsingError::UnexpectedToken,
+                offset: value_len + whitespace_trimmed,
+            })
+        }
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
+/// Removes a UTF-8 byte order mark from the very start of `contents`, returning its length
+fn strip_bom(contents: &str) -> (&str, usize) {
+    match contents.strip_prefix('\u{feff}') {
+        Some(rest) => (rest, contents.len() - rest.len()),
+        None => (contents, 0),
+    }
+}
+
+/// The offset of the first key in `contents` repeating an earlier key of the same object
+///
+/// `contents` must hold a single value which has already been scanned successfully.  Each object
+/// is checked separately, rather than recursing into its members.
+pub(crate) fn find_duplicate_key(contents: &str, parser: Parser) -> Option<usize> {
+    let parser = parser.deny_duplicate_keys(false);
+    let tokens = Tokens { contents, parser };
+    for token in tokens.filter(|&token| token == "{") {
+        let start = token.as_ptr() as usize - contents.as_ptr() as usize;
+        let object = parser.load(&contents[start..]);
+        let keys = object.iter_keys().ok()?;
+        for (index, key) in keys.enumerate() {
+            let key = key.ok()?;
+            let mut earlier_keys = object.iter_keys().ok()?.take(index);
+            if earlier_keys.any(|earlier| earlier.is_ok_and(|k| decode_key(k).eq(decode_key(key))))
+            {
+                // Step back over the opening quote
+                return Some(key.as_ptr() as usize - contents.as_ptr() as usize - 1);
+            }
+        }
+    }
+    None
+}
+
+/// A fixed-size stack recording whether each enclosing container is an object or an array
+///
+/// The parser uses this instead of recursion, so that parsing takes a bounded amount of stack.
+/// Each level takes one bit of the buffer it is given.
+pub(crate) struct NestingStack<'s> {
+    is_object: &'s mut [u8],
+    len: usize,
+}
+
+impl<'s> NestingStack<'s> {
+    pub(crate) fn new(is_object: &'s mut [u8]) -> NestingStack<'s> {
+        NestingStack { is_object, len: 0 }
+    }
+
+    /// The most levels the stack can hold
+    pub(crate) fn capacity(&self) -> usize {
+        self.is_object.len().saturating_mul(8)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Records a newly opened container.  The stack must not already be full.
+    pub(crate) fn push(&mut self, is_object: bool) {
+        let (byte, bit) = (self.len / 8, self.len % 8);
+        if is_object {
+            self.is_object[byte] |= 1 << bit;
+        } else {
+            self.is_object[byte] &= !(1 << bit);
+        }
+        self.len += 1;
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.len -= 1;
+    }
+
+    /// Whether the innermost container is an object, or `None` if there is no container
+    pub(crate) fn top(&self) -> Option<bool> {
+        let index = self.len.checked_sub(1)?;
+        Some(self.is_object[index / 8] & (1 << (index % 8)) != 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::JSONValueType;
+    extern crate std;
+
+    #[test]
+    fn depth_limit() {
+        let nested = "[".repeat(10_000) + &"]".repeat(10_000);
+        let error = JSONValue::parse(&nested).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::DepthLimitExceeded);
+        assert_eq!(error.offset, DEFAULT_MAX_DEPTH);
+        assert!(JSONValue::load(&nested).verify().is_err());
+
+        let nested = "[".repeat(DEFAULT_MAX_DEPTH) + &"]".repeat(DEFAULT_MAX_DEPTH);
+        assert!(JSONValue::parse(&nested).is_ok());
+
+        let parser = Parser::new().max_depth(2);
+        assert!(parser.parse("[{\"a\": 1}, []]").is_ok());
+        let error = parser.parse("[{\"a\": [1]}]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::DepthLimitExceeded);
+        assert_eq!(error.offset, 7);
+        assert!(Parser::new().max_depth(0).parse("1").is_ok());
+        assert!(Parser::new().max_depth(0).parse("[]").is_err());
+
+        // The limit applies relative to whichever value is being parsed
+        let value = parser.load("[[[1]]]");
+        assert!(value.verify().is_err());
+        let inner = value.iter_array().unwrap().next().unwrap();
+        assert_eq!(inner.value_type, JSONValueType::Array);
+        assert!(inner.verify().is_ok());
+    }
+
+    #[test]
+    fn nesting_without_recursion() {
+        // A thread with a stack far too small to recurse through every level
+        std::thread::Builder::new()
+            .stack_size(32 * 1024)
+            .spawn(|| {
+                let parser = Parser::new().max_depth(usize::MAX);
+                let nested = "[{\"a\":".repeat(MAX_NESTING_DEPTH / 2)
+                    + "1"
+                    + &"}]".repeat(MAX_NESTING_DEPTH / 2);
+                let (_, value_len) = parser.parse(&nested).unwrap();
+                assert_eq!(value_len, nested.len());
+
+                let nested = "[".repeat(100_000) + &"]".repeat(100_000);
+                let error = parser.parse(&nested).unwrap_err();
+                assert_eq!(error.kind, JSONParsingError::DepthLimitExceeded);
+                assert_eq!(error.offset, MAX_NESTING_DEPTH);
+
+                // A large enough stack from the caller lifts that limit
+                let mut stack = std::vec![0; 100_000 / 8];
+                let (value, value_len) = parser.parse_with_stack(&nested, &mut stack).unwrap();
+                assert_eq!(value.value_type, JSONValueType::Array);
+                assert_eq!(value_len, nested.len());
+                // Accessors use the usual stack, so report the depth rather than a wrong answer
+                let too_deep = JSONParsingError::DepthLimitExceeded;
+                assert_eq!(value.len(), Err(too_deep));
+                assert_eq!(value.is_empty(), Err(too_deep));
+                assert_eq!(value.get_nth_array_item(0).unwrap_err(), too_deep);
+                assert_eq!(value.verify().unwrap_err().kind, too_deep);
+                assert!(value.iter_array().unwrap().next().is_none());
+                let nested = "[{\"a\":".repeat(50_000) + "1" + &"}]".repeat(50_000);
+                let (_, value_len) = parser.parse_with_stack(&nested, &mut stack).unwrap();
+                assert_eq!(value_len, nested.len());
+                let nested = "[{\"a\":".repeat(50_000) + "1" + &"]}".repeat(50_000);
+                let error = parser.parse_with_stack(&nested, &mut stack).unwrap_err();
+                assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+
+                let nested = "[".repeat(100_001) + &"]".repeat(100_001);
+                let error = parser.parse_with_stack(&nested, &mut stack).unwrap_err();
+                assert_eq!(error.kind, JSONParsingError::DepthLimitExceeded);
+                assert_eq!(error.offset, 100_000);
+                let error = Parser::new()
+                    .parse_with_stack(&nested, &mut stack)
+                    .unwrap_err();
+                assert_eq!(error.offset, DEFAULT_MAX_DEPTH);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(JSONValue::parse("[{\"a\": [1]}, {}, [[]]]").is_ok());
+        assert!(JSONValue::parse("[}").is_err());
+        assert!(JSONValue::parse("{\"a\": 1]").is_err());
+        assert!(JSONValue::parse("[{\"a\": 1]}").is_err());
+        assert!(JSONValue::parse("[[1], {\"a\": [}]").is_err());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let parser = Parser::new().deny_duplicate_keys(true);
+        assert!(JSONValue::parse("{\"a\": 1, \"a\": 2}").is_ok());
+
+        let error = parser.parse("{\"a\": 1, \"a\": 2}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::DuplicateKey);
+        assert_eq!(error.offset, 9);
+        let error = parser
+            .parse(" [{\"a\": {\"b\": 1, \"c\": 2, \"b\": 3}}]")
+            .unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::DuplicateKey);
+        assert_eq!(error.offset, 25);
+        assert!(parser.parse("{\"a\": 1, \"\\u0061\": 2}").is_err());
+        assert!(parser.load_and_verify("{\"a\": 1, \"a\": 2}").is_err());
+
+        // Keys only collide with others in the same object
+        assert!(parser.parse("[{\"a\": 1}, {\"a\": 2}]").is_ok());
+        assert!(parser
+            .parse("{\"a\": {\"a\": 1}, \"b\": [{\"a\": 2}]}")
+            .is_ok());
+        assert!(parser
+            .parse("{\"a\": \"{\\\"a\\\": 1, \\\"a\\\": 2}\"}")
+            .is_ok());
+        assert!(parser.parse("{\"a\": 1, \"ab\": 2, \"b\": 3}").is_ok());
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let (value, value_len) = JSONValue::parse("\u{feff}{\"a\":1}").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Object);
+        assert_eq!(value.get_key_value("a").unwrap().read_integer(), Ok(1));
+        assert_eq!(value_len, 3 + 7);
+
+        let error = JSONValue::parse("\u{feff}[1, ]").unwrap_err();
+        assert_eq!(error.offset, 3 + 4);
+        assert!(JSONValue::parse_exact("\u{feff} [1]").is_ok());
+        assert!(JSONValue::load_and_verify("\u{feff}[1]").is_ok());
+        assert_eq!(
+            JSONValue::load("\u{feff}[1]").value_type,
+            JSONValueType::Array
+        );
+
+        // Only a mark at the very start is skipped
+        assert!(JSONValue::parse(" \u{feff}[1]").is_err());
+        assert!(JSONValue::parse("[1, \u{feff}2]").is_err());
+        assert!(JSONValue::parse("\u{feff}\u{feff}[1]").is_err());
+        for (contents, offset) in [(" \u{feff}[1]", 1), ("\u{feff}\u{feff}[1]", 3)] {
+            let error = JSONValue::load_and_verify(contents).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+            assert_eq!(error.offset, offset);
+            assert_eq!(JSONValue::parse(contents).unwrap_err(), error);
+            assert_eq!(JSONValue::load(contents).value_type, JSONValueType::Error);
+        }
+    }
+
+    #[test]
+    fn unicode_whitespace() {
+        let error = JSONValue::parse("\u{a0}1").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 0);
+        let error = JSONValue::parse("[1,\u{b}2]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 3);
+        assert!(JSONValue::parse_exact("1\u{3000}").is_err());
+        assert!(
+            JSONValue::parse_exact(" \t\r\n{ \t\r\n\"a\" \t\r\n: \t\r\n1 \t\r\n} \t\r\n").is_ok()
+        );
+
+        let parser = Parser::new().allow_unicode_whitespace(true);
+        assert!(parser.parse("\u{a0}1").is_ok());
+        assert!(parser.parse("[1,\u{b}2]").is_ok());
+        assert!(parser.parse_exact("1\u{3000}").is_ok());
+        let value = parser.load("{\u{a0}\"a\"\u{a0}:\u{a0}[1,\u{a0}2]\u{a0}}");
+        assert!(value.verify().is_ok());
+        let array = value.get_key_value("a").unwrap();
+        assert_eq!(array.iter_array().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn lenient_numbers() {
+        let parser = Parser::new().allow_lenient_numbers(true);
+        assert_eq!(parser.load(".5").read_float(), Ok(0.5));
+        assert_eq!(parser.load(".5").read_f64(), Ok(0.5));
+        assert_eq!(parser.load("-.25e1").read_f64(), Ok(-2.5));
+        assert_eq!(parser.load("+3").read_integer(), Ok(3));
+        assert_eq!(parser.load("+3").read_u64(), Ok(3));
+        assert_eq!(parser.load("+3.5").read_float(), Ok(3.5));
+        assert!(parser.load("+3").is_integer());
+        let value = parser.load_and_verify("[.5, +3, 1]").unwrap();
+        assert_eq!(value.get_nth_array_item(1).unwrap().read_integer(), Ok(3));
+
+        // Neither makes other malformed numbers acceptable
+        for number in ["+-3", "-+3", "++3", ".", "+", "+.", ".e5", "5.", "+03"] {
+            assert!(parser.parse(number).is_err(), "{}", number);
+        }
+
+        // Strict parsing still rejects them
+        for number in [".5", "+3"] {
+            let error = JSONValue::parse(number).unwrap_err();
+            assert_eq!(error.kind, JSONParsingError::InvalidNumber);
+            assert_eq!(
+                JSONValue::load(number).read_float(),
+                Err(JSONParsingError::CannotParseFloat)
+            );
+        }
+        assert_eq!(
+            JSONValue::load("+3").read_integer(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let parser = Parser::new().allow_comments(true);
+        let contents = "{ // note\n \"a\": 1 /* inline */ }";
+        let value = parser.load_and_verify(contents).unwrap();
+        assert_eq!(value.get_key_value("a").unwrap().read_integer(), Ok(1));
+        assert!(JSONValue::parse(contents).is_err());
+
+        let contents = "/* leading */ [ // first\n 1 /* a */, /**/ 2 // last\r\n] // trailing";
+        let value = parser.parse_exact(contents).unwrap();
+        assert_eq!(value.len(), Ok(2));
+        assert_eq!(value.get_nth_array_item(1).unwrap().read_integer(), Ok(2));
+        let value = parser
+            .load("{\"a\" /* before colon */ : /* after colon */ [1 /**/] /*,*/ , \"b\"//\n: 2}");
+        assert!(value.verify().is_ok());
+        assert_eq!(value.get_key_value("b").unwrap().read_integer(), Ok(2));
+        assert_eq!(value.iter_keys().unwrap().count(), 2);
+
+        // Comment markers within strings are just text
+        let value = parser.load("[\"// not a comment\", \"/* nor this */\"]");
+        assert_eq!(value.len(), Ok(2));
+        assert_eq!(
+            value
+                .get_nth_array_item(0)
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("// not a comment")
+        );
+
+        let error = parser.parse("[1 /* unterminated ]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 3);
+        assert!(parser.parse("[1 / 2]").is_err());
+        assert!(parser.parse_exact("1 /* */ 2").is_err());
+        assert!(parser
+            .deny_duplicate_keys(true)
+            .parse("{\"a\": 1 /* {\"b\": 1, \"b\": 2} */}")
+            .is_ok());
+
+        for contents in ["// c\n1", "[1 /* c */]", "{\"a\": 1 // c\n}"] {
+            assert!(JSONValue::parse(contents).is_err(), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let parser = Parser::new().allow_trailing_commas(true);
+
+        let value = parser.parse_exact("[1, 2, 3, ]").unwrap();
+        assert_eq!(value.len(), Ok(3));
+        // Iterate forwards to the end, rather than letting clippy swap in `next_back`
+        #[allow(clippy::double_ended_iterator_last)]
+        let last = value.iter_array().unwrap().last().unwrap();
+        assert_eq!(last.read_integer(), Ok(3));
+        let last = value.iter_array().unwrap().next_back().unwrap();
+        assert_eq!(last.read_integer(), Ok(3));
+        let error = JSONValue::parse("[1, 2, 3, ]").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 10);
+
+        let value = parser.parse_exact(r#"{"a": 1, "b": [2,],}"#).unwrap();
+        assert_eq!(value.len(), Ok(2));
+        assert_eq!(value.get_key_value("b").unwrap().len(), Ok(1));
+        assert!(value.iter_object().unwrap().all(|member| member.is_ok()));
+        let error = JSONValue::parse(r#"{"a": 1,}"#).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 8);
+
+        for contents in ["[,]", "{,}", "[1,,]", r#"{"a": 1,,}"#, "[1,"] {
+            assert!(parser.parse(contents).is_err(), "{}", contents);
+        }
+        let parser = parser.allow_comments(true);
+        assert!(parser.parse("[1, /* more to come */ ]").is_ok());
+    }
+
+    #[test]
+    fn single_quotes() {
+        let parser = Parser::new().allow_single_quotes(true);
+        let value = parser.parse_exact("{'name':'bob'}").unwrap();
+        assert_eq!(
+            value
+                .get_key_value("name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("bob")
+        );
+        assert_eq!(value.iter_keys().unwrap().next(), Some(Ok("name")));
+        let error = JSONValue::parse("{'name':'bob'}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 1);
+        assert_eq!(
+            JSONValue::load("'bob'").read_string(&mut [0; 64]),
+            Err(JSONParsingError::UnexpectedToken)
+        );
+
+        // Each kind of quote may appear unescaped within the other
+        let value = parser.load(r#"['say "hi"', "it's", 'it\'s', 'a\u0062\n']"#);
+        assert!(value.verify().is_ok());
+        let items: std::vec::Vec<_> = value.iter_array().unwrap().collect();
+        assert_eq!(items[0].read_string(&mut [0; 64]), Ok(r#"say "hi""#));
+        assert_eq!(items[1].read_string(&mut [0; 64]), Ok("it's"));
+        assert!(items[2].iter_string().unwrap().eq("it's".chars().map(Ok)));
+        assert!(items[3].iter_string().unwrap().eq("ab\n".chars().map(Ok)));
+        assert_eq!(
+            JSONValue::load(r#""it\'s""#).iter_string().unwrap().last(),
+            Some(Err(JSONParsingError::InvalidEscapeSequence('\'')))
+        );
+
+        let value = parser.load(r#"{'a"b': 1, "c'd": 2, 'e\'f': 3}"#);
+        assert_eq!(value.get_key_value("a\"b").unwrap().read_integer(), Ok(1));
+        assert_eq!(value.get_key_value("c'd").unwrap().read_integer(), Ok(2));
+        assert_eq!(value.get_key_value("e'f").unwrap().read_integer(), Ok(3));
+        assert!(parser
+            .deny_duplicate_keys(true)
+            .parse(r#"{'a': 1, "a": 2}"#)
+            .is_err());
+
+        for contents in ["'unterminated", "'mismatched\"", "['a\tb']"] {
+            assert!(parser.parse(contents).is_err(), "{:?}", contents);
+        }
+    }
+
+    #[test]
+    fn unquoted_keys() {
+        let parser = Parser::new().allow_unquoted_keys(true);
+        let value = parser
+            .parse_exact(r#"{ name: "bob", _id$2 : 7, "quoted": true }"#)
+            .unwrap();
+        assert_eq!(
+            value
+                .get_key_value("name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("bob")
+        );
+        assert_eq!(value.get_key_value("_id$2").unwrap().read_integer(), Ok(7));
+        assert!(value.contains_key("quoted"));
+        let keys: Result<std::vec::Vec<_>, _> = value.iter_keys().unwrap().collect();
+        assert_eq!(keys.unwrap(), ["name", "_id$2", "quoted"]);
+        assert!(parser
+            .deny_duplicate_keys(true)
+            .parse(r#"{a: 1, "a": 2}"#)
+            .is_err());
+
+        let error = JSONValue::parse(r#"{ name: "bob" }"#).unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::UnexpectedToken);
+        assert_eq!(error.offset, 2);
+        assert_eq!(
+            JSONValue::load(r#"{ name: "bob" }"#)
+                .get_key_value("name")
+                .unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+
+        // Only keys may be left unquoted
+        for contents in [
+            "{2nd: 1}",
+            "{a-b: 1}",
+            "{a b: 1}",
+            "{a}",
+            r#"{"a": bob}"#,
+            "[bob]",
+        ] {
+            assert!(parser.parse(contents).is_err(), "{}", contents);
+        }
+    }
+
+    #[test]
+    fn composed_options() {
+        let contents = "\u{feff}{\n  // Settings\n  name: 'bob',\n  ratio: .5,\n  nested: {deep: [[+1,],],},\n}\n";
+        let value = Parser::lenient().load_and_verify(contents).unwrap();
+        assert_eq!(
+            value
+                .get_key_value("name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("bob")
+        );
+        assert_eq!(value.get_key_value("ratio").unwrap().read_float(), Ok(0.5));
+        let deep = value
+            .get_key_value("nested")
+            .unwrap()
+            .get_key_value("deep")
+            .unwrap();
+        let inner = deep
+            .get_nth_array_item(0)
+            .unwrap()
+            .get_nth_array_item(0)
+            .unwrap();
+        assert_eq!(inner.read_integer(), Ok(1));
+        assert!(JSONValue::load_and_verify(contents).is_err());
+
+        // Turning off any one option rejects the document again
+        let without = [
+            Parser::lenient().allow_comments(false),
+            Parser::lenient().allow_single_quotes(false),
+            Parser::lenient().allow_unquoted_keys(false),
+            Parser::lenient().allow_trailing_commas(false),
+            Parser::lenient().allow_lenient_numbers(false),
+            Parser::lenient().max_depth(3),
+        ];
+        for parser in without {
+            assert!(parser.load_and_verify(contents).is_err(), "{:?}", parser);
+        }
+        assert!(Parser::lenient()
+            .max_depth(4)
+            .load_and_verify(contents)
+            .is_ok());
+
+        let parser = Parser::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true)
+            .deny_duplicate_keys(true);
+        assert!(parser.parse_exact("{\"a\": 1, /* \"a\": 2, */}").is_ok());
+        let error = parser.parse("{\"a\": 1, // once\n \"a\": 2,}").unwrap_err();
+        assert_eq!(error.kind, JSONParsingError::DuplicateKey);
+        assert_eq!(error.offset, 18);
+        assert_eq!(Parser::default(), Parser::new());
+    }
+
+    #[test]
+    fn nan_and_infinity() {
+        let parser = Parser::new().allow_nan_and_infinity(true);
+        let value = parser.parse_exact("NaN").unwrap();
+        assert_eq!(value.value_type, JSONValueType::Number);
+        assert!(value.read_f64().unwrap().is_nan());
+        assert!(value.read_float().unwrap().is_nan());
+        assert_eq!(parser.load("Infinity").read_f64(), Ok(f64::INFINITY));
+        assert_eq!(parser.load("-Infinity").read_f64(), Ok(f64::NEG_INFINITY));
+        assert_eq!(parser.load("-Infinity").read_float(), Ok(f32::NEG_INFINITY));
+        assert_eq!(
+            parser.load("Infinity").read_integer(),
+            Err(JSONParsingError::CannotParseInteger)
+        );
+        assert!(!parser.load("NaN").is_integer());
+
+        let value = parser.parse_exact("[NaN, Infinity, -Infinity, 1]").unwrap();
+        assert_eq!(value.len(), Ok(4));
+        let infinity = value.get_nth_array_item(1).unwrap();
+        let one = value.get_nth_array_item(3).unwrap();
+        assert_eq!(infinity.number_cmp(&one), Ok(core::cmp::Ordering::Greater));
+        let nan = value.get_nth_array_item(0).unwrap();
+        assert_eq!(
+            nan.number_cmp(&one),
+            Err(JSONParsingError::CannotParseFloat)
+        );
+
+        for contents in ["NaN", "Infinity", "-Infinity", "-NaN", "[NaN]"] {
+            assert!(JSONValue::parse(contents).is_err(), "{}", contents);
+            assert!(
+                JSONValue::load(contents).read_f64().is_err(),
+                "{}",
+                contents
+            );
+        }
+        for contents in ["nan", "inf", "-NaNa", "Infinit", "NaN1"] {
+            assert!(parser.parse_exact(contents).is_err(), "{}", contents);
+        }
+
+        // Without the option, the literals aren't taken to be numbers at all
+        assert!(parser.load("NaN").is_number());
+        assert!(!JSONValue::load("NaN").is_number());
+        assert!(!JSONValue::load("Infinity").is_number());
+    }
+}
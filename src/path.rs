@@ -0,0 +1,436 @@
+use crate::{decode_key, JSONParsingError, JSONValue, JSONValueType};
+
+/// Looks up a value through a path of keys and indices, such as `"users"[0]."name"`
+///
+/// Keys are string literals and indices are bracketed expressions, and may be separated with
+/// dots.  The macro expands to calls to [`JSONValue::get_key_value`] and
+/// [`JSONValue::get_nth_array_item`], giving a `Result` which holds the first error found.
+///
+/// ### Example
+/// ```
+/// # use microjson::{json_get, JSONValue};
+/// let root = JSONValue::load(r#"{"users": [{"name": "Ada", "langs": ["en", "fr"]}]}"#);
+/// let name = json_get!(root, "users"[0]."name").and_then(|name| name.read_string_raw());
+/// assert_eq!(name, Ok("Ada"));
+/// let second = 1;
+/// assert!(json_get!(root, "users"[0]."langs"[second]).is_ok());
+/// assert!(json_get!(root, "users"[1]).is_err());
+/// ```
+#[macro_export]
+macro_rules! json_get {
+    (@walk $value:expr, ) => {
+        $value
+    };
+    (@walk $value:expr, . $($rest:tt)*) => {
+        $crate::json_get!(@walk $value, $($rest)*)
+    };
+    (@walk $value:expr, [$index:expr] $($rest:tt)*) => {
+        $crate::json_get!(@walk $value.and_then(|value| value.get_nth_array_item($index)), $($rest)*)
+    };
+    (@walk $value:expr, $key:literal $($rest:tt)*) => {
+        $crate::json_get!(@walk $value.and_then(|value| value.get_key_value($key)), $($rest)*)
+    };
+    ($value:expr, $($path:tt)*) => {
+        $crate::json_get!(
+            @walk ::core::result::Result::<$crate::JSONValue, $crate::JSONParsingError>::Ok($value),
+            $($path)*
+        )
+    };
+}
+
+/// One step of a path passed to [`JSONValue::deep_get`]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Segment<'s> {
+    /// The member of an object with this key
+    Key(&'s str),
+    /// The element of an array at this index
+    Index(usize),
+}
+
+impl<'a> JSONValue<'a> {
+    /// Looks up a value by an [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901) JSON Pointer
+    ///
+    /// The pointer is a sequence of `/`-prefixed reference tokens, each of which is either an
+    /// object key or an array index.  Within a token, `~1` stands for `/` and `~0` for `~`.  The
+    /// empty pointer refers to this value itself.
+    ///
+    /// Returns `Err(JSONParsingError::KeyNotFound)` if any token doesn't refer to an existing
+    /// member, `Err(JSONParsingError::IndexOutOfBounds)` if an index is past the end of an array
+    /// and `Err(JSONParsingError::InvalidPointer)` if the pointer is malformed.  The `-` token,
+    /// which refers to the element after the last, is never found.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"users": [{"name": "Ada"}, {"name": "Grace"}]}"#);
+    /// let name = value.get_pointer("/users/1/name").unwrap();
+    /// assert_eq!(name.read_string(&mut [0; 16]), Ok("Grace"));
+    /// ```
+    pub fn get_pointer(&self, pointer: &str) -> Result<JSONValue<'a>, JSONParsingError> {
+        if pointer.is_empty() {
+            return Ok(*self);
+        }
+        let pointer = pointer
+            .strip_prefix('/')
+            .ok_or(JSONParsingError::InvalidPointer)?;
+        let mut value = *self;
+        for token in pointer.split('/') {
+            value = match value.value_type {
+                JSONValueType::Object => {
+                    if !is_valid_pointer_token(token) {
+                        return Err(JSONParsingError::InvalidPointer);
+                    }
+                    value.find_member(|key| {
+                        decode_pointer_token(token).map(Ok).eq(decode_key(key))
+                    })?
+                }
+                JSONValueType::Array => value.element_at(token)?,
+                _ => return Err(JSONParsingError::NotAContainer),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Looks up a value by a dot-delimited path such as `"server.ports.0"`
+    ///
+    /// Each segment is used as a key if the value it is applied to is an object, or as an index if
+    /// it is an array.  The empty path refers to this value itself.
+    ///
+    /// Keys containing a `.` cannot be reached with this method, as the path is split on every
+    /// dot.  Use [`JSONValue::get_pointer`] for such keys.
+    ///
+    /// Returns `Err(JSONParsingError::KeyNotFound)` if any segment doesn't refer to an existing
+    /// member, and `Err(JSONParsingError::IndexOutOfBounds)` if an index is past the end of an
+    /// array.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"server": {"ports": [80, 443]}}"#);
+    /// assert_eq!(value.get_path("server.ports.1").unwrap().read_integer(), Ok(443));
+    /// ```
+    pub fn get_path(&self, path: &str) -> Result<JSONValue<'a>, JSONParsingError> {
+        if path.is_empty() {
+            return Ok(*self);
+        }
+        let mut value = *self;
+        for segment in path.split('.') {
+            value = match value.value_type {
+                JSONValueType::Object => value.get_key_value(segment)?,
+                JSONValueType::Array => value.element_at(segment)?,
+                _ => return Err(JSONParsingError::NotAContainer),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Looks up a value by a path of pre-split segments
+    ///
+    /// Each [`Segment::Key`] must be applied to an object and each [`Segment::Index`] to an array,
+    /// so unlike [`JSONValue::get_path`] a numeric key is never mistaken for an index.  The empty
+    /// path refers to this value itself.
+    ///
+    /// Returns `Err(JSONParsingError::KeyNotFound)` if a key doesn't refer to an existing member,
+    /// `Err(JSONParsingError::IndexOutOfBounds)` if an index is past the end of an array, and
+    /// `Err(JSONParsingError::CannotParseObject)` or `Err(JSONParsingError::CannotParseArray)` if
+    /// a segment is applied to the wrong type of value.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::{JSONValue, Segment};
+    /// let value = JSONValue::load(r#"{"users": [{"name": "Ada"}, {"name": "Grace"}]}"#);
+    /// let path = [Segment::Key("users"), Segment::Index(1), Segment::Key("name")];
+    /// assert_eq!(value.deep_get(&path).unwrap().read_string(&mut [0; 64]), Ok("Grace"));
+    /// ```
+    pub fn deep_get(&self, path: &[Segment<'_>]) -> Result<JSONValue<'a>, JSONParsingError> {
+        let mut value = *self;
+        for segment in path {
+            value = match *segment {
+                Segment::Key(key) => value.get_key_value(key)?,
+                Segment::Index(index) => value.get_nth_array_item(index)?,
+            };
+        }
+        Ok(value)
+    }
+
+    /// The value of the first member of this object whose key satisfies `matches`
+    fn find_member<F: Fn(&str) -> bool>(
+        &self,
+        matches: F,
+    ) -> Result<JSONValue<'a>, JSONParsingError> {
+        for item in self.iter_object()? {
+            let (key, value) = item?;
+            if matches(key) {
+                return Ok(value);
+            }
+        }
+        Err(JSONParsingError::KeyNotFound)
+    }
+
+    /// The element of this array at the index written in `index`
+    ///
+    /// Only plain digits without leading zeros are accepted as an index.
+    fn element_at(&self, index: &str) -> Result<JSONValue<'a>, JSONParsingError> {
+        if !index.bytes().all(|b| b.is_ascii_digit()) || (index.len() > 1 && index.starts_with('0'))
+        {
+            return Err(JSONParsingError::KeyNotFound);
+        }
+        let index = index
+            .parse::<usize>()
+            .map_err(|_| JSONParsingError::KeyNotFound)?;
+        self.get_nth_array_item(index)
+    }
+}
+
+/// Whether every `~` in a JSON Pointer reference token is part of a `~0` or `~1` escape
+fn is_valid_pointer_token(token: &str) -> bool {
+    let mut chars = token.chars();
+    while let Some(chr) = chars.next() {
+        if chr == '~' && !matches!(chars.next(), Some('0' | '1')) {
+            return false;
+        }
+    }
+    true
+}
+
+/// The characters of a valid JSON Pointer reference token, with escapes resolved
+fn decode_pointer_token(token: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = token.chars();
+    core::iter::from_fn(move || match chars.next()? {
+        '~' => match chars.next() {
+            Some('1') => Some('/'),
+            _ => Some('~'),
+        },
+        chr => Some(chr),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_pointer() {
+        let value = JSONValue::load(
+            r#"{
+                "users": [{"name": "Ada", "tags": ["a", "b"]}, {"name": "Grace"}],
+                "a/b": 1,
+                "m~n": 2,
+                "~1": 3,
+                "": 4,
+                "\u0061\/c": 5
+            }"#,
+        );
+        assert_eq!(
+            value.get_pointer("").unwrap().value_type,
+            JSONValueType::Object
+        );
+        assert_eq!(
+            value
+                .get_pointer("/users/0/name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("Ada")
+        );
+        assert_eq!(
+            value
+                .get_pointer("/users/0/tags/1")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("b")
+        );
+        assert_eq!(
+            value
+                .get_pointer("/users/1/name")
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("Grace")
+        );
+        assert_eq!(value.get_pointer("/a~1b").unwrap().read_integer(), Ok(1));
+        assert_eq!(value.get_pointer("/m~0n").unwrap().read_integer(), Ok(2));
+        assert_eq!(value.get_pointer("/~01").unwrap().read_integer(), Ok(3));
+        assert_eq!(value.get_pointer("/").unwrap().read_integer(), Ok(4));
+        assert_eq!(value.get_pointer("/a~1c").unwrap().read_integer(), Ok(5));
+
+        assert_eq!(
+            value.get_pointer("/users/2").err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            value.get_pointer("/users/-").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_pointer("/users/01").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_pointer("/users/+1").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_pointer("/nobody").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_pointer("/a~2b").err(),
+            Some(JSONParsingError::InvalidPointer)
+        );
+        assert_eq!(
+            value.get_pointer("users").err(),
+            Some(JSONParsingError::InvalidPointer)
+        );
+        assert_eq!(
+            value.get_pointer("/users/0/name/first").err(),
+            Some(JSONParsingError::NotAContainer)
+        );
+    }
+
+    #[test]
+    fn dotted_path() {
+        let value = JSONValue::load(
+            r#"{"a": {"b": [{"c": 1}, {"c": 2}], "3": "three"}, "x.y": 4, "n": null}"#,
+        );
+        assert_eq!(value.get_path("a.b.0.c").unwrap().read_integer(), Ok(1));
+        assert_eq!(value.get_path("a.b.1.c").unwrap().read_integer(), Ok(2));
+        assert_eq!(
+            value.get_path("a.3").unwrap().read_string(&mut [0; 64]),
+            Ok("three")
+        );
+        assert_eq!(
+            value.get_path("").unwrap().value_type,
+            JSONValueType::Object
+        );
+
+        assert_eq!(
+            value.get_path("a.b.2.c").err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            value.get_path("a.b.c").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_path("a.z").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_path("x.y").err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            value.get_path("n.0").err(),
+            Some(JSONParsingError::NotAContainer)
+        );
+    }
+
+    #[test]
+    fn segment_paths() {
+        let value = JSONValue::load(
+            r#"{
+                "matrix": [[1, 2], [3, {"0": "zero", "k\u0065y": [true]}]],
+                "1": "one"
+            }"#,
+        );
+        use Segment::{Index, Key};
+        assert_eq!(
+            value.deep_get(&[]).unwrap().value_type,
+            JSONValueType::Object
+        );
+        assert_eq!(
+            value
+                .deep_get(&[Key("matrix"), Index(0), Index(1)])
+                .unwrap()
+                .read_integer(),
+            Ok(2)
+        );
+        let inner = [Key("matrix"), Index(1), Index(1)];
+        assert_eq!(
+            value
+                .deep_get(&[inner[0], inner[1], inner[2], Key("0")])
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("zero")
+        );
+        assert_eq!(
+            value
+                .deep_get(&[inner[0], inner[1], inner[2], Key("key"), Index(0)])
+                .unwrap()
+                .read_bool(),
+            Ok(true)
+        );
+        assert_eq!(
+            value
+                .deep_get(&[Key("1")])
+                .unwrap()
+                .read_string(&mut [0; 64]),
+            Ok("one")
+        );
+
+        // Numeric keys and indices are never confused
+        assert_eq!(
+            value.deep_get(&inner).unwrap().deep_get(&[Index(0)]).err(),
+            Some(JSONParsingError::CannotParseArray)
+        );
+        assert_eq!(
+            value.deep_get(&[Key("matrix"), Key("0")]).err(),
+            Some(JSONParsingError::CannotParseObject)
+        );
+        assert_eq!(
+            value.deep_get(&[Key("matrix"), Index(2)]).err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            value.deep_get(&[Key("missing")]).err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+    }
+
+    #[test]
+    fn path_macro() {
+        let root = JSONValue::load(
+            r#"{
+                "users": [{"name": "Ada", "langs": ["en", "fr"]}, {"name": "Grace"}],
+                "grid": [[1, 2], [3, 4]],
+                "0": "zero"
+            }"#,
+        );
+        assert_eq!(
+            json_get!(root, "users"[0]."name").and_then(|value| value.read_string_raw()),
+            Ok("Ada")
+        );
+        assert_eq!(
+            json_get!(root, "users"[1] "name").and_then(|value| value.read_string_raw()),
+            Ok("Grace")
+        );
+        let (row, column) = (1, 0);
+        assert_eq!(
+            json_get!(root, "grid"[row][column]).and_then(|value| value.read_integer()),
+            Ok(3)
+        );
+        assert_eq!(
+            json_get!(root, "users"[0]."langs"[row * 2 - 1])
+                .and_then(|value| value.read_string_raw()),
+            Ok("fr")
+        );
+        assert_eq!(
+            json_get!(root, "0").and_then(|value| value.read_string_raw()),
+            Ok("zero")
+        );
+        assert_eq!(json_get!(root,).unwrap().value_type, JSONValueType::Object);
+
+        // The first missing segment is reported
+        assert_eq!(
+            json_get!(root, "users"[2]."name").err(),
+            Some(JSONParsingError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            json_get!(root, "users"[0]."age"[0]).err(),
+            Some(JSONParsingError::KeyNotFound)
+        );
+        assert_eq!(
+            json_get!(root, "grid"."0").err(),
+            Some(JSONParsingError::CannotParseObject)
+        );
+    }
+}
@@ -0,0 +1,698 @@
+#[cfg(feature = "alloc")]
+use crate::JSONValueType;
+use crate::{EscapedStringIterator, JSONParsingError, JSONValue, Tokens};
+use core::fmt::Write;
+
+/// Escapes `s` as the contents of a JSON string into `buf`, returning the number of bytes written
+///
+/// Quotes, backslashes and control characters are escaped, using the short escapes such as `\n`
+/// where there is one.  The surrounding quotes are not written.  If the escaped string doesn't
+/// fit, returns `Err(JSONParsingError::BufferTooSmall)`.
+///
+/// ### Example
+/// ```
+/// # use microjson::escape_str_into;
+/// let mut buf = [0; 32];
+/// let len = escape_str_into("say \"hi\"\n", &mut buf).unwrap();
+/// assert_eq!(&buf[..len], br#"say \"hi\"\n"#);
+/// ```
+pub fn escape_str_into(s: &str, buf: &mut [u8]) -> Result<usize, JSONParsingError> {
+    let mut writer = SliceWriter { buf, len: 0 };
+    write_escaped(&mut writer, s).map_err(|_| JSONParsingError::BufferTooSmall)?;
+    Ok(writer.len)
+}
+
+/// Writes JSON to a [`Write`], one token at a time
+///
+/// Commas and colons are inserted between members and elements, and keys and strings are escaped.
+/// The writer doesn't check the structure of what is written, so each object and array must be
+/// ended by the caller, and each object member given a key before its value.
+///
+/// ### Example
+/// ```
+/// # use microjson::JSONWriter;
+/// # fn main() -> core::fmt::Result {
+/// let mut writer = JSONWriter::new(String::new());
+/// writer.begin_object()?;
+/// writer.key("name")?;
+/// writer.value_str("Ada")?;
+/// writer.key("tags")?;
+/// writer.begin_array()?;
+/// writer.value_i64(1)?;
+/// writer.value_bool(false)?;
+/// writer.end_array()?;
+/// writer.end_object()?;
+/// assert_eq!(writer.into_inner(), r#"{"name":"Ada","tags":[1,false]}"#);
+/// # Ok(())
+/// # }
+/// ```
+pub struct JSONWriter<W: Write> {
+    writer: W,
+    // Set once a value has been written in the current container, so the next needs a comma
+    needs_comma: bool,
+    // Set after a key, so its value follows the colon directly
+    after_key: bool,
+}
+
+impl<W: Write> JSONWriter<W> {
+    /// A writer which writes to `writer`
+    pub fn new(writer: W) -> JSONWriter<W> {
+        JSONWriter {
+            writer,
+            needs_comma: false,
+            after_key: false,
+        }
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Starts an object, whose members are written with [`JSONWriter::key`] and a value
+    pub fn begin_object(&mut self) -> core::fmt::Result {
+        self.begin_value()?;
+        self.needs_comma = false;
+        self.writer.write_char('{')
+    }
+
+    /// Ends the innermost object
+    pub fn end_object(&mut self) -> core::fmt::Result {
+        self.needs_comma = true;
+        self.writer.write_char('}')
+    }
+
+    /// Starts an array
+    pub fn begin_array(&mut self) -> core::fmt::Result {
+        self.begin_value()?;
+        self.needs_comma = false;
+        self.writer.write_char('[')
+    }
+
+    /// Ends the innermost array
+    pub fn end_array(&mut self) -> core::fmt::Result {
+        self.needs_comma = true;
+        self.writer.write_char(']')
+    }
+
+    /// Writes the key of an object member, which must be followed by its value
+    pub fn key(&mut self, key: &str) -> core::fmt::Result {
+        self.begin_value()?;
+        self.writer.write_char('"')?;
+        write_escaped(&mut self.writer, key)?;
+        self.writer.write_str("\":")?;
+        self.after_key = true;
+        Ok(())
+    }
+
+    /// Writes a string value, escaping it as needed
+    pub fn value_str(&mut self, value: &str) -> core::fmt::Result {
+        self.begin_value()?;
+        self.writer.write_char('"')?;
+        write_escaped(&mut self.writer, value)?;
+        self.writer.write_char('"')
+    }
+
+    /// Writes an integer value
+    pub fn value_i64(&mut self, value: i64) -> core::fmt::Result {
+        self.begin_value()?;
+        write!(self.writer, "{}", value)
+    }
+
+    /// Writes a boolean value
+    pub fn value_bool(&mut self, value: bool) -> core::fmt::Result {
+        self.begin_value()?;
+        self.writer.write_str(if value { "true" } else { "false" })
+    }
+
+    /// Writes a null value
+    pub fn value_null(&mut self) -> core::fmt::Result {
+        self.begin_value()?;
+        self.writer.write_str("null")
+    }
+
+    /// Writes the comma needed before a value or key, if any
+    fn begin_value(&mut self) -> core::fmt::Result {
+        if self.after_key {
+            self.after_key = false;
+        } else if self.needs_comma {
+            self.writer.write_char(',')?;
+        }
+        self.needs_comma = true;
+        Ok(())
+    }
+}
+
+/// Writes `s` as the contents of a JSON string, escaping the characters which need it
+fn write_escaped<W: Write>(w: &mut W, s: &str) -> core::fmt::Result {
+    let mut rest = s;
+    while let Some(index) = rest.find(|chr: char| chr == '"' || chr == '\\' || chr < '\u{20}') {
+        w.write_str(&rest[..index])?;
+        // Each character needing an escape is a single byte
+        match rest.as_bytes()[index] {
+            b'"' => w.write_str("\\\"")?,
+            b'\\' => w.write_str("\\\\")?,
+            b'\n' => w.write_str("\\n")?,
+            b'\r' => w.write_str("\\r")?,
+            b'\t' => w.write_str("\\t")?,
+            0x08 => w.write_str("\\b")?,
+            0x0c => w.write_str("\\f")?,
+            byte => write!(w, "\\u{:04x}", byte)?,
+        }
+        rest = &rest[index + 1..];
+    }
+    w.write_str(rest)
+}
+
+/// Writes a string, number or literal token, rewriting the parser's extensions to JSON as JSON
+///
+/// `is_key` is set for a token followed by a colon, which may be an unquoted key.
+fn write_token<W: Write>(w: &mut W, token: &str, is_key: bool) -> core::fmt::Result {
+    if let Some(contents) = token.strip_prefix('\'') {
+        let chars = EscapedStringIterator {
+            contents: contents.chars(),
+            quote: '\'',
+            done: false,
+        };
+        w.write_char('"')?;
+        for chr in chars {
+            let chr = chr.map_err(|_| core::fmt::Error)?;
+            write_escaped(w, chr.encode_utf8(&mut [0; 4]))?;
+        }
+        w.write_char('"')
+    } else if is_key && !token.starts_with('"') {
+        w.write_char('"')?;
+        write_escaped(w, token)?;
+        w.write_char('"')
+    } else if token.starts_with(['-', '+', '.', 'N', 'I']) {
+        let unsigned = token.trim_start_matches('+');
+        let (sign, digits) = match unsigned.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", unsigned),
+        };
+        if digits.starts_with(['N', 'I']) {
+            return Err(core::fmt::Error);
+        }
+        w.write_str(sign)?;
+        if digits.starts_with('.') {
+            w.write_char('0')?;
+        }
+        w.write_str(digits)
+    } else {
+        w.write_str(token)
+    }
+}
+
+/// A [`Write`] filling a byte buffer, which fails rather than write a partial string
+struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let target = self
+            .buf
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(core::fmt::Error)?;
+        target.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+impl<'a> JSONValue<'a> {
+    /// Writes this value as JSON without any insignificant whitespace
+    ///
+    /// Strings and numbers are written exactly as they appear in the input, except where the
+    /// parser's extensions to JSON are rewritten as JSON: comments and trailing commas are left
+    /// out, unquoted keys and single-quoted strings are written in double quotes, and numbers such
+    /// as `+3` and `.5` become `3` and `0.5`.  `NaN` and `Infinity` have no JSON form, so values
+    /// containing them give an error.  If the value is not valid, returns an error without
+    /// writing anything.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{ "a" : [ 1 , 2 ] }"#);
+    /// let mut minified = String::new();
+    /// value.write_minified(&mut minified).unwrap();
+    /// assert_eq!(minified, r#"{"a":[1,2]}"#);
+    /// ```
+    pub fn write_minified<W: Write>(&self, w: &mut W) -> core::fmt::Result {
+        let mut tokens = self.tokens()?.peekable();
+        while let Some(token) = tokens.next() {
+            write_token(w, token, tokens.peek() == Some(&":"))?;
+        }
+        Ok(())
+    }
+
+    /// Writes this value as JSON with each member or element on its own line
+    ///
+    /// Nested members and elements are indented by `indent` spaces per level, while empty objects
+    /// and arrays are kept on one line.  Strings and numbers are written as by
+    /// [`JSONValue::write_minified`], so the parser's extensions to JSON are rewritten as JSON.  If
+    /// the value is not valid, returns an error without writing anything.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"a":[1,2],"b":{}}"#);
+    /// let mut pretty = String::new();
+    /// value.write_pretty(&mut pretty, 2).unwrap();
+    /// assert_eq!(pretty, "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}");
+    /// ```
+    pub fn write_pretty<W: Write>(&self, w: &mut W, indent: usize) -> core::fmt::Result {
+        let mut tokens = self.tokens()?.peekable();
+        let mut depth = 0;
+        while let Some(token) = tokens.next() {
+            match token {
+                "{" | "[" => {
+                    w.write_str(token)?;
+                    if let Some(&closing @ ("}" | "]")) = tokens.peek() {
+                        w.write_str(closing)?;
+                        tokens.next();
+                    } else {
+                        depth += 1;
+                        write!(w, "\n{:1$}", "", depth * indent)?;
+                    }
+                }
+                "}" | "]" => {
+                    depth -= 1;
+                    write!(w, "\n{:1$}{2}", "", depth * indent, token)?;
+                }
+                "," => write!(w, ",\n{:1$}", "", depth * indent)?,
+                ":" => w.write_str(": ")?,
+                _ => write_token(w, token, tokens.peek() == Some(&":"))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes this value as canonical JSON, following the JSON Canonicalization Scheme of RFC 8785
+    ///
+    /// Object members are sorted by the UTF-16 code units of their decoded keys, numbers are
+    /// written in their shortest form which reads back as the same `f64`, and strings are written
+    /// with only the escapes JSON requires.  Equal values therefore give identical output, which
+    /// can be hashed or signed.  If the value is not valid JSON, or holds a number which is not
+    /// finite or a string with a lone surrogate, returns an error.  If `w` fails, returns
+    /// `Err(JSONParsingError::BufferTooSmall)`.  Only available with the `alloc` feature.
+    ///
+    /// ### Example
+    /// ```
+    /// # use microjson::JSONValue;
+    /// let value = JSONValue::load(r#"{"b": [1.50, 2e3], "a": "\u0041"}"#);
+    /// let mut canonical = String::new();
+    /// value.write_canonical(&mut canonical).unwrap();
+    /// assert_eq!(canonical, r#"{"a":"A","b":[1.5,2000]}"#);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn write_canonical<W: Write>(&self, w: &mut W) -> Result<(), JSONParsingError> {
+        self.verify()?;
+        self.write_canonical_unchecked(w)
+    }
+
+    /// Writes this value, which must be valid, as canonical JSON
+    #[cfg(feature = "alloc")]
+    fn write_canonical_unchecked<W: Write>(&self, w: &mut W) -> Result<(), JSONParsingError> {
+        let fail = |_| JSONParsingError::BufferTooSmall;
+        match self.value_type {
+            JSONValueType::Object => {
+                let mut members = self
+                    .iter_object()?
+                    .collect::<Result<alloc::vec::Vec<_>, _>>()?;
+                members.sort_by(|(a, _), (b, _)| utf16_units(a).cmp(utf16_units(b)));
+                w.write_char('{').map_err(fail)?;
+                for (index, (key, value)) in members.into_iter().enumerate() {
+                    if index > 0 {
+                        w.write_char(',').map_err(fail)?;
+                    }
+                    write_canonical_string(w, crate::decode_key(key))?;
+                    w.write_char(':').map_err(fail)?;
+                    value.write_canonical_unchecked(w)?;
+                }
+                w.write_char('}').map_err(fail)
+            }
+            JSONValueType::Array => {
+                w.write_char('[').map_err(fail)?;
+                for (index, item) in self.iter_array()?.enumerate() {
+                    if index > 0 {
+                        w.write_char(',').map_err(fail)?;
+                    }
+                    item.write_canonical_unchecked(w)?;
+                }
+                w.write_char(']').map_err(fail)
+            }
+            JSONValueType::String => write_canonical_string(w, self.iter_string()?),
+            JSONValueType::Number => write_canonical_number(w, self.read_f64()?),
+            JSONValueType::Bool | JSONValueType::Null => self.write_minified(w).map_err(fail),
+            JSONValueType::Error => Err(JSONParsingError::UnexpectedToken),
+        }
+    }
+
+    /// The tokens making up this value, which must be valid
+    ///
+    /// Trailing commas allowed by the parser are left out.
+    fn tokens(&self) -> Result<impl Iterator<Item = &'a str>, core::fmt::Error> {
+        let (_, value_len) =
+            JSONValue::parse_with_len(self.contents, self.parser).map_err(|_| core::fmt::Error)?;
+        let mut tokens = Tokens {
+            contents: &self.contents[..value_len],
+            parser: self.parser,
+        }
+        .peekable();
+        Ok(core::iter::from_fn(move || match tokens.next()? {
+            "," if matches!(tokens.peek(), Some(&("}" | "]"))) => tokens.next(),
+            token => Some(token),
+        }))
+    }
+}
+
+/// The UTF-16 code units of a key once its escapes are decoded, by which RFC 8785 sorts members
+#[cfg(feature = "alloc")]
+fn utf16_units(key: &str) -> impl Iterator<Item = u16> + '_ {
+    crate::decode_key(key).flatten().flat_map(|chr| {
+        let mut units = [0; 2];
+        let len = chr.encode_utf16(&mut units).len();
+        IntoIterator::into_iter(units).take(len)
+    })
+}
+
+/// Writes a decoded string, quoted and with only the escapes which JSON requires
+#[cfg(feature = "alloc")]
+fn write_canonical_string<W: Write>(
+    w: &mut W,
+    chars: impl Iterator<Item = Result<char, JSONParsingError>>,
+) -> Result<(), JSONParsingError> {
+    let fail = |_| JSONParsingError::BufferTooSmall;
+    w.write_char('"').map_err(fail)?;
+    for chr in chars {
+        write_escaped(w, chr?.encode_utf8(&mut [0; 4])).map_err(fail)?;
+    }
+    w.write_char('"').map_err(fail)
+}
+
+/// Writes a number the way ECMAScript converts it to a string, as RFC 8785 requires
+///
+/// The digits are the shortest which read back as `number`, written in full if the decimal point
+/// falls within 21 digits of them, and with an exponent otherwise.
+#[cfg(feature = "alloc")]
+fn write_canonical_number<W: Write>(w: &mut W, number: f64) -> Result<(), JSONParsingError> {
+    if number.is_nan() {
+        return Err(JSONParsingError::InvalidNumber);
+    } else if number.is_infinite() {
+        return Err(JSONParsingError::NumberOverflow);
+    }
+    let fail = |_| JSONParsingError::BufferTooSmall;
+    if number == 0.0 {
+        // Negative zero is written without its sign
+        return w.write_char('0').map_err(fail);
+    } else if number < 0.0 {
+        w.write_char('-').map_err(fail)?;
+    }
+
+    // Rust writes the shortest digits in scientific notation, such as `1.25e-7`
+    let mut buf = [0; 32];
+    let mut scientific = SliceWriter {
+        buf: &mut buf,
+        len: 0,
+    };
+    write!(scientific, "{:e}", number.abs()).map_err(fail)?;
+    let len = scientific.len;
+    let scientific =
+        core::str::from_utf8(&buf[..len]).map_err(|_| JSONParsingError::InvalidUtf8)?;
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .ok_or(JSONParsingError::InvalidNumber)?;
+    let exponent: i32 = exponent
+        .parse()
+        .map_err(|_| JSONParsingError::InvalidNumber)?;
+    let (first, rest) = mantissa.split_at(1);
+    let rest = rest.trim_start_matches('.');
+    let digits = (rest.len() + 1) as i32;
+    // The number is `0.{first}{rest}` times ten to the `point`
+    let point = exponent + 1;
+
+    if digits <= point && point <= 21 {
+        write!(w, "{}{}{:0>3$}", first, rest, "", (point - digits) as usize).map_err(fail)
+    } else if 0 < point && point <= 21 {
+        let (whole, fraction) = mantissa.split_at(point as usize + 1);
+        write!(w, "{}{}.{}", first, &whole[2..], fraction).map_err(fail)
+    } else if -6 < point && point <= 0 {
+        write!(w, "0.{:0>1$}{2}{3}", "", -point as usize, first, rest).map_err(fail)
+    } else if rest.is_empty() {
+        write!(w, "{}e{:+}", first, exponent).map_err(fail)
+    } else {
+        write!(w, "{}.{}e{:+}", first, rest, exponent).map_err(fail)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Parser;
+    extern crate std;
+    use std::string::String;
+
+    fn minified(contents: &str) -> Result<String, core::fmt::Error> {
+        let mut output = String::new();
+        JSONValue::load(contents).write_minified(&mut output)?;
+        Ok(output)
+    }
+
+    fn pretty(contents: &str, indent: usize) -> Result<String, core::fmt::Error> {
+        let mut output = String::new();
+        JSONValue::load(contents).write_pretty(&mut output, indent)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn pretty_printing() {
+        assert_eq!(pretty("[1,2]", 2).unwrap(), "[\n  1,\n  2\n]");
+        assert_eq!(pretty("[1,2]", 0).unwrap(), "[\n1,\n2\n]");
+        assert_eq!(pretty(" { } ", 4).unwrap(), "{}");
+        assert_eq!(pretty("[[ ]]", 4).unwrap(), "[\n    []\n]");
+        assert_eq!(pretty("\"a,b\"", 4).unwrap(), "\"a,b\"");
+        assert_eq!(
+            pretty(r#"{"a": {"b": [true, null]}, "c": "x:y"}"#, 3).unwrap(),
+            "{\n   \"a\": {\n      \"b\": [\n         true,\n         null\n      ]\n   },\n   \"c\": \"x:y\"\n}"
+        );
+        assert!(pretty("[1,", 2).is_err());
+
+        let mut out = String::new();
+        Parser::lenient()
+            .load("{a:'b', c: [.5,]}")
+            .write_pretty(&mut out, 2)
+            .unwrap();
+        assert_eq!(out, "{\n  \"a\": \"b\",\n  \"c\": [\n    0.5\n  ]\n}");
+    }
+
+    #[test]
+    fn minifying() {
+        assert_eq!(
+            minified(r#"{ "a" : [ 1 , 2 ] }"#).unwrap(),
+            r#"{"a":[1,2]}"#
+        );
+        assert_eq!(
+            minified("\n{\n  \"a b\": \"x, y: \\\" z\",\n  \"c\": [true, false, null, -1.5e3, {}, []]\n}\n")
+                .unwrap(),
+            r#"{"a b":"x, y: \" z","c":[true,false,null,-1.5e3,{},[]]}"#
+        );
+        assert_eq!(minified(" 42 ").unwrap(), "42");
+        assert_eq!(minified(r#"  "  spaced  "  "#).unwrap(), r#""  spaced  ""#);
+        assert_eq!(minified("[1] [2]").unwrap(), "[1]");
+        assert!(minified("[1, x]").is_err());
+        assert!(minified("{").is_err());
+
+        let value = Parser::new()
+            .allow_comments(true)
+            .load("[1/* one */, // two\n 2, \"/* kept */\"] // trailing");
+        let mut out = String::new();
+        value.write_minified(&mut out).unwrap();
+        assert_eq!(out, r#"[1,2,"/* kept */"]"#);
+
+        let value = Parser::new()
+            .allow_trailing_commas(true)
+            .load(r#"{"a": [1, 2, ], "b": {"c": 3,},}"#);
+        let mut out = String::new();
+        value.write_minified(&mut out).unwrap();
+        assert_eq!(out, r#"{"a":[1,2],"b":{"c":3}}"#);
+
+        // The other extensions are rewritten as JSON too
+        let value = Parser::lenient().load(r#"{a:'b', c:[.5,+3,-.25,'it\'s "x"',],'d':null,}"#);
+        let mut out = String::new();
+        value.write_minified(&mut out).unwrap();
+        assert_eq!(out, r#"{"a":"b","c":[0.5,3,-0.25,"it's \"x\""],"d":null}"#);
+        assert!(JSONValue::is_valid(&out));
+        for contents in ["NaN", "[-Infinity]", "{a: Infinity}"] {
+            let mut out = String::new();
+            assert!(Parser::lenient()
+                .load(contents)
+                .write_minified(&mut out)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn escaping() {
+        let escaped = |s: &str| -> Result<String, JSONParsingError> {
+            let mut buf = [0; 64];
+            let len = escape_str_into(s, &mut buf)?;
+            Ok(String::from(core::str::from_utf8(&buf[..len]).unwrap()))
+        };
+        assert_eq!(escaped("plain"), Ok(String::from("plain")));
+        assert_eq!(
+            escaped("a \"quote\", a \\ and a\nnewline"),
+            Ok(String::from(r#"a \"quote\", a \\ and a\nnewline"#))
+        );
+        assert_eq!(
+            escaped("\t\r\x08\x0c\x00\x1f\x7f/"),
+            Ok(String::from("\\t\\r\\b\\f\\u0000\\u001f\x7f/"))
+        );
+        assert_eq!(escaped("café ☕"), Ok(String::from("café ☕")));
+        assert_eq!(escaped(""), Ok(String::new()));
+
+        // Escaped strings decode back to the original
+        let original = "tab\there \"quoted\" \\ \u{1}";
+        let mut buf = [0; 64];
+        buf[0] = b'"';
+        let len = escape_str_into(original, &mut buf[1..]).unwrap();
+        buf[len + 1] = b'"';
+        let json = core::str::from_utf8(&buf[..len + 2]).unwrap();
+        assert!(JSONValue::load(json)
+            .iter_string()
+            .unwrap()
+            .eq(original.chars().map(Ok)));
+
+        let mut buf = [0; 4];
+        assert_eq!(escape_str_into("abcd", &mut buf), Ok(4));
+        assert_eq!(
+            escape_str_into("abc\n", &mut buf),
+            Err(JSONParsingError::BufferTooSmall)
+        );
+        assert_eq!(
+            escape_str_into("abcde", &mut buf),
+            Err(JSONParsingError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn json_writer() {
+        let mut writer = JSONWriter::new(String::new());
+        writer.begin_object().unwrap();
+        writer.key("a").unwrap();
+        writer.value_i64(1).unwrap();
+        writer.key("b").unwrap();
+        writer.begin_array().unwrap();
+        writer.value_bool(true).unwrap();
+        writer.value_null().unwrap();
+        writer.end_array().unwrap();
+        writer.end_object().unwrap();
+        assert_eq!(writer.into_inner(), r#"{"a":1,"b":[true,null]}"#);
+
+        let mut writer = JSONWriter::new(String::new());
+        writer.begin_array().unwrap();
+        writer.begin_object().unwrap();
+        writer.end_object().unwrap();
+        writer.begin_array().unwrap();
+        writer.end_array().unwrap();
+        writer.begin_object().unwrap();
+        writer.key("quote \" key").unwrap();
+        writer.value_str("line\nbreak").unwrap();
+        writer.key("n").unwrap();
+        writer.value_i64(i64::MIN).unwrap();
+        writer.end_object().unwrap();
+        writer.value_str("").unwrap();
+        writer.end_array().unwrap();
+        let json = writer.into_inner();
+        assert_eq!(
+            json,
+            r#"[{},[],{"quote \" key":"line\nbreak","n":-9223372036854775808},""]"#
+        );
+        let value = JSONValue::parse_exact(&json).unwrap();
+        let member = value.get_nth_array_item(2).unwrap();
+        assert!(member.get_key_value("quote \" key").unwrap() == "line\nbreak");
+
+        let mut writer = JSONWriter::new(String::new());
+        writer.value_bool(false).unwrap();
+        assert_eq!(writer.into_inner(), "false");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn canonical_output() {
+        fn canonical(contents: &str) -> Result<String, JSONParsingError> {
+            let mut output = String::new();
+            JSONValue::load(contents).write_canonical(&mut output)?;
+            Ok(output)
+        }
+
+        // The examples from RFC 8785, sections 3.2.2 and 3.2.3, with their members reordered
+        assert_eq!(
+            canonical(
+                r#"{
+                    "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                    "literals": [null, true, false],
+                    "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001]
+                }"#
+            )
+            .unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+        assert_eq!(
+            canonical(
+                r#"{
+                    "\u20ac": "Euro Sign",
+                    "\r": "Carriage Return",
+                    "\ufb33": "Hebrew Letter Dalet With Dagesh",
+                    "1": "One",
+                    "\ud83d\ude00": "Emoji: Grinning Face",
+                    "\u0080": "Control",
+                    "\u00f6": "Latin Small Letter O With Diaeresis"
+                }"#
+            )
+            .unwrap(),
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\
+             \"ö\":\"Latin Small Letter O With Diaeresis\",\"€\":\"Euro Sign\",\
+             \"😀\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+        );
+
+        // Numbers are written as ECMAScript would write them
+        assert_eq!(
+            canonical(
+                "[0, -0.0, 1, -1.5, 1e20, 1e21, 123456789e-3, 1e-6, 1.5e-7, 9007199254740993]"
+            )
+            .unwrap(),
+            "[0,0,1,-1.5,100000000000000000000,1e+21,123456.789,0.000001,1.5e-7,9007199254740992]"
+        );
+        assert_eq!(
+            canonical("[5e-324, -1.7976931348623157e308, 0.1, 12e-1]").unwrap(),
+            "[5e-324,-1.7976931348623157e+308,0.1,1.2]"
+        );
+
+        assert_eq!(
+            canonical(r#" { "a" : { } , "b" : [ ] } "#).unwrap(),
+            r#"{"a":{},"b":[]}"#
+        );
+        assert_eq!(
+            canonical("{'b': 1}").unwrap_err(),
+            JSONParsingError::UnexpectedToken
+        );
+        let mut output = String::new();
+        Parser::lenient()
+            .load("{'b': 1, a: [2,], /* c */ \"\\u0061b\": 3}")
+            .write_canonical(&mut output)
+            .unwrap();
+        assert_eq!(output, r#"{"a":[2],"ab":3,"b":1}"#);
+
+        assert_eq!(
+            canonical("1e400").unwrap_err(),
+            JSONParsingError::NumberOverflow
+        );
+        assert_eq!(
+            canonical(r#""\ud834""#).unwrap_err(),
+            JSONParsingError::InvalidUnicodeEscapeSequence
+        );
+        assert_eq!(canonical("[1,").unwrap_err(), JSONParsingError::EndOfStream);
+    }
+}
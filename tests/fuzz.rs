@@ -0,0 +1,171 @@
+use microjson::*;
+
+/// A small deterministic xorshift generator, so failures can be reproduced from the seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Appends a random value, using the lenient extensions to JSON as well as JSON itself
+fn random_value(rng: &mut Rng, out: &mut String, depth: usize) {
+    const SCALARS: &[&str] = &[
+        "0",
+        "-1",
+        "12.5e-3",
+        "1E400",
+        "-0.0",
+        "+1",
+        ".5",
+        "NaN",
+        "-Infinity",
+        "true",
+        "false",
+        "null",
+        r#""""#,
+        r#""a""#,
+        r#""\"\\\/\b\f\n\r\t""#,
+        r#""\u00e9\ud834\udd1e""#,
+        "\"é😀\"",
+        "'single'",
+        r#""\ud834""#,
+        r#""\x""#,
+    ];
+    const SPACE: &[&str] = &["", "", " ", "\n\t", "\r\n", "/* c */", "// c\n", "\u{a0}"];
+    out.push_str(SPACE[rng.below(SPACE.len())]);
+    match rng.below(if depth == 0 { 2 } else { 6 }) {
+        0 | 1 => out.push_str(SCALARS[rng.below(SCALARS.len())]),
+        2 | 3 => {
+            out.push('[');
+            for n in 0..rng.below(4) {
+                if n > 0 {
+                    out.push(',');
+                }
+                random_value(rng, out, depth - 1);
+            }
+            out.push_str(if rng.below(8) == 0 { ",]" } else { "]" });
+        }
+        _ => {
+            const KEYS: &[&str] = &[
+                r#""a""#,
+                r#""b""#,
+                r#""\u0061""#,
+                "'a'",
+                "a",
+                r#""A""#,
+                r#""""#,
+            ];
+            out.push('{');
+            for n in 0..rng.below(4) {
+                if n > 0 {
+                    out.push(',');
+                }
+                out.push_str(KEYS[rng.below(KEYS.len())]);
+                out.push_str(SPACE[rng.below(SPACE.len())]);
+                out.push(':');
+                random_value(rng, out, depth - 1);
+            }
+            out.push_str(if rng.below(8) == 0 { ",}" } else { "}" });
+        }
+    }
+    out.push_str(SPACE[rng.below(SPACE.len())]);
+}
+
+/// Random input, made by damaging a random document in a few places
+fn random_input(rng: &mut Rng) -> Vec<u8> {
+    const BYTES: &[u8] = b"{}[],:\"'\\/*0.eE+-tnu \n\xc3\xa9\xff\xed";
+    let mut document = String::new();
+    random_value(rng, &mut document, 4);
+    let mut input = document.into_bytes();
+    for _ in 0..rng.below(4) {
+        let at = rng.below(input.len() + 1);
+        match rng.below(4) {
+            0 => input.truncate(at),
+            1 => input.insert(at, BYTES[rng.below(BYTES.len())]),
+            _ if at < input.len() => {
+                input.remove(at);
+            }
+            _ => {}
+        }
+    }
+    input
+}
+
+/// Calls every accessor on `value`, and on its members, ignoring the results
+fn exercise(value: JSONValue<'_>, depth: usize) {
+    let _ = value.verify();
+    let _ = value.read_integer();
+    let _ = value.read_i64();
+    let _ = value.read_u64();
+    let _ = value.read_float();
+    let _ = value.read_f64();
+    let _ = value.read_bool();
+    let _ = value.read_string(&mut [0; 64]);
+    let _ = value.read_string_raw();
+    let _ = value.read_char();
+    let _ = value.string_decoded_len();
+    let _ = value.string_utf16_len();
+    let _ = value.string_is_ascii();
+    let _ = value.unescape_into(&mut [0; 8]);
+    let _ = value.is_integer();
+    let _ = value.len();
+    let _ = value.raw();
+    let _ = value.to_string();
+    let _ = value.get_key_value("a");
+    let _ = value.get_key_value_ci("A");
+    let _ = value.get_nth_array_item(1);
+    let _ = value.get_pointer("/a/0");
+    let _ = value.get_path("a.0");
+    let _ = value.nth_entry(1);
+    let _ = value.number_cmp(&value);
+    let _ = value.semantic_eq(&value);
+    let _ = value.write_minified(&mut String::new());
+    let _ = value.write_pretty(&mut String::new(), 2);
+    if let Ok(chars) = value.iter_string() {
+        chars.for_each(drop);
+    }
+    if depth == 0 {
+        return;
+    }
+    if let Ok(items) = value.iter_array() {
+        items
+            .rev()
+            .take(4)
+            .for_each(|item| exercise(item, depth - 1));
+    }
+    if let Ok(members) = value.iter_object() {
+        for (_, member) in members.take(4).flatten() {
+            exercise(member, depth - 1);
+        }
+    }
+}
+
+#[test]
+fn random_input_never_panics() {
+    let parsers = [Parser::new(), Parser::lenient().deny_duplicate_keys(true)];
+    for seed in 1..20_000u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let input = random_input(&mut rng);
+        for parser in parsers {
+            if let Ok((value, value_len)) = parser.parse_bytes(&input) {
+                assert!(value_len <= input.len(), "seed {}", seed);
+                exercise(value, 2);
+            }
+            // Values which are loaded without being checked must be just as safe to use
+            if let Ok(contents) = std::str::from_utf8(&input) {
+                exercise(parser.load(contents), 2);
+                let _ = parser.parse_exact(contents);
+                parser.parse_stream(contents).take(8).for_each(drop);
+            }
+        }
+    }
+}
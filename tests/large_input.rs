@@ -11,7 +11,7 @@ fn large_input_read() {
             .unwrap()
             .get_key_value("_id")
             .unwrap()
-            .read_string(),
+            .read_string(&mut [0; 64]),
         Ok("6136737d845794b21eddadc0")
     );
     assert_eq!(
@@ -28,7 +28,7 @@ fn large_input_read() {
             .unwrap()
             .get_key_value("name")
             .unwrap()
-            .read_string(),
+            .read_string(&mut [0; 64]),
         Ok("Rosario Curtis")
     );
 }
@@ -49,7 +49,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"cashmccray@bostonic.com\",
     \"phone\": \"+1 (967) 462-3122\",
     \"address\": \"354 Degraw Street, Edinburg, Missouri, 547\",
-    \"about\": \"Ad veniam ad esse ea ut magna reprehenderit veniam. Minim ex est elit enim nisi id exercitation non id ipsum magna. Cillum commodo tempor qui nostrud reprehenderit id consectetur consequat.\r\n\",
+    \"about\": \"Ad veniam ad esse ea ut magna reprehenderit veniam. Minim ex est elit enim nisi id exercitation non id ipsum magna. Cillum commodo tempor qui nostrud reprehenderit id consectetur consequat.\\r\\n\",
     \"registered\": \"2017-06-20T07:24:55 -01:00\",
     \"latitude\": 85.233704,
     \"longitude\": 13.95815,
@@ -94,7 +94,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"stacycardenas@keengen.com\",
     \"phone\": \"+1 (956) 479-3419\",
     \"address\": \"930 Llama Court, Summerset, Guam, 2791\",
-    \"about\": \"Occaecat nisi labore amet ut minim ex Lorem commodo enim proident dolore laborum cillum ad. Minim do qui esse aliquip ut nostrud mollit consequat. Laborum officia mollit anim ad nulla pariatur irure magna consectetur duis officia. Eu quis non eiusmod velit veniam. Adipisicing eu dolore nulla commodo et adipisicing aliquip culpa enim amet dolor amet. Do reprehenderit consectetur labore enim velit id.\r\n\",
+    \"about\": \"Occaecat nisi labore amet ut minim ex Lorem commodo enim proident dolore laborum cillum ad. Minim do qui esse aliquip ut nostrud mollit consequat. Laborum officia mollit anim ad nulla pariatur irure magna consectetur duis officia. Eu quis non eiusmod velit veniam. Adipisicing eu dolore nulla commodo et adipisicing aliquip culpa enim amet dolor amet. Do reprehenderit consectetur labore enim velit id.\\r\\n\",
     \"registered\": \"2017-11-25T06:01:14 -00:00\",
     \"latitude\": 10.036853,
     \"longitude\": -49.255527,
@@ -139,7 +139,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"romansolis@signity.com\",
     \"phone\": \"+1 (812) 537-3964\",
     \"address\": \"648 Merit Court, Waumandee, Indiana, 8393\",
-    \"about\": \"Consectetur eu in exercitation pariatur amet nisi fugiat culpa irure dolor ad quis laborum. Fugiat laborum eu culpa in laboris aliquip est proident deserunt adipisicing consequat id laborum esse. In cupidatat cillum officia ex excepteur veniam elit aute ut tempor. Lorem exercitation ut id fugiat irure in consectetur cupidatat Lorem consequat qui. Sit ex incididunt et quis reprehenderit Lorem magna ipsum.\r\n\",
+    \"about\": \"Consectetur eu in exercitation pariatur amet nisi fugiat culpa irure dolor ad quis laborum. Fugiat laborum eu culpa in laboris aliquip est proident deserunt adipisicing consequat id laborum esse. In cupidatat cillum officia ex excepteur veniam elit aute ut tempor. Lorem exercitation ut id fugiat irure in consectetur cupidatat Lorem consequat qui. Sit ex incididunt et quis reprehenderit Lorem magna ipsum.\\r\\n\",
     \"registered\": \"2014-12-27T02:08:36 -00:00\",
     \"latitude\": 52.682357,
     \"longitude\": -55.960438,
@@ -184,7 +184,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"riverahale@gazak.com\",
     \"phone\": \"+1 (930) 491-3216\",
     \"address\": \"677 Diamond Street, Coloma, West Virginia, 2197\",
-    \"about\": \"Aliquip cillum laboris laborum anim aliqua minim Lorem ea labore. Sit non nostrud ea Lorem velit nisi aliqua magna aliquip labore minim nulla laborum labore. Lorem minim tempor ad ut sint incididunt anim eiusmod nulla.\r\n\",
+    \"about\": \"Aliquip cillum laboris laborum anim aliqua minim Lorem ea labore. Sit non nostrud ea Lorem velit nisi aliqua magna aliquip labore minim nulla laborum labore. Lorem minim tempor ad ut sint incididunt anim eiusmod nulla.\\r\\n\",
     \"registered\": \"2020-08-30T05:28:26 -01:00\",
     \"latitude\": -73.144359,
     \"longitude\": 53.215709,
@@ -229,7 +229,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"donaldsonmcintyre@architax.com\",
     \"phone\": \"+1 (905) 517-3690\",
     \"address\": \"208 Newton Street, Bison, Michigan, 9227\",
-    \"about\": \"Laborum magna consequat ea non duis excepteur. Ipsum irure ullamco sunt deserunt sunt eiusmod dolor do aute reprehenderit dolor laboris. Pariatur cillum dolor voluptate eiusmod ad tempor adipisicing dolor ipsum. Pariatur ut occaecat occaecat ipsum id.\r\n\",
+    \"about\": \"Laborum magna consequat ea non duis excepteur. Ipsum irure ullamco sunt deserunt sunt eiusmod dolor do aute reprehenderit dolor laboris. Pariatur cillum dolor voluptate eiusmod ad tempor adipisicing dolor ipsum. Pariatur ut occaecat occaecat ipsum id.\\r\\n\",
     \"registered\": \"2017-10-19T11:22:10 -01:00\",
     \"latitude\": -6.008692,
     \"longitude\": -17.349018,
@@ -274,7 +274,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"ladonnacompton@olucore.com\",
     \"phone\": \"+1 (938) 419-2099\",
     \"address\": \"902 Stockholm Street, Morgandale, Texas, 5356\",
-    \"about\": \"Deserunt eu fugiat occaecat culpa ullamco commodo incididunt dolor proident exercitation. Excepteur anim exercitation nostrud magna. Voluptate quis Lorem occaecat nostrud officia laboris esse. Do dolore consectetur sint ipsum sunt enim labore ullamco id duis deserunt sint elit. Ullamco adipisicing esse aute ad mollit aute culpa eu ipsum culpa est Lorem et. Occaecat excepteur quis anim duis nulla sit nulla nostrud tempor aute exercitation aliqua ipsum.\r\n\",
+    \"about\": \"Deserunt eu fugiat occaecat culpa ullamco commodo incididunt dolor proident exercitation. Excepteur anim exercitation nostrud magna. Voluptate quis Lorem occaecat nostrud officia laboris esse. Do dolore consectetur sint ipsum sunt enim labore ullamco id duis deserunt sint elit. Ullamco adipisicing esse aute ad mollit aute culpa eu ipsum culpa est Lorem et. Occaecat excepteur quis anim duis nulla sit nulla nostrud tempor aute exercitation aliqua ipsum.\\r\\n\",
     \"registered\": \"2019-05-21T05:29:13 -01:00\",
     \"latitude\": -8.531072,
     \"longitude\": -7.093529,
@@ -319,7 +319,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"dominiquedixon@snips.com\",
     \"phone\": \"+1 (915) 551-3738\",
     \"address\": \"220 Tapscott Street, Johnsonburg, New York, 249\",
-    \"about\": \"Qui consequat amet adipisicing irure ex enim sint fugiat eu dolor ea non magna proident. Magna eiusmod id ullamco ad tempor cillum excepteur cupidatat fugiat fugiat anim. Ullamco elit et Lorem ut. Quis eiusmod anim adipisicing aliquip laboris ullamco ipsum officia.\r\n\",
+    \"about\": \"Qui consequat amet adipisicing irure ex enim sint fugiat eu dolor ea non magna proident. Magna eiusmod id ullamco ad tempor cillum excepteur cupidatat fugiat fugiat anim. Ullamco elit et Lorem ut. Quis eiusmod anim adipisicing aliquip laboris ullamco ipsum officia.\\r\\n\",
     \"registered\": \"2018-07-19T11:53:46 -01:00\",
     \"latitude\": -57.166103,
     \"longitude\": 73.98064,
@@ -364,7 +364,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"cunninghampugh@accupharm.com\",
     \"phone\": \"+1 (905) 400-2716\",
     \"address\": \"498 Albee Square, Forestburg, Federated States Of Micronesia, 7103\",
-    \"about\": \"Aliquip excepteur ea ad consectetur ea non sunt duis. Pariatur et incididunt laborum ex occaecat amet velit. Fugiat id excepteur sunt laborum cillum minim ut. Est duis cupidatat ea laboris dolore tempor ut nulla ipsum in proident. Fugiat ad cillum est nulla irure sit nisi laborum eiusmod qui est aliqua. Non sit culpa adipisicing commodo aute fugiat esse consectetur laboris sunt ad eiusmod.\r\n\",
+    \"about\": \"Aliquip excepteur ea ad consectetur ea non sunt duis. Pariatur et incididunt laborum ex occaecat amet velit. Fugiat id excepteur sunt laborum cillum minim ut. Est duis cupidatat ea laboris dolore tempor ut nulla ipsum in proident. Fugiat ad cillum est nulla irure sit nisi laborum eiusmod qui est aliqua. Non sit culpa adipisicing commodo aute fugiat esse consectetur laboris sunt ad eiusmod.\\r\\n\",
     \"registered\": \"2018-12-01T06:49:38 -00:00\",
     \"latitude\": -29.710314,
     \"longitude\": -161.367617,
@@ -409,7 +409,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"judithpadilla@terrago.com\",
     \"phone\": \"+1 (886) 587-3828\",
     \"address\": \"382 Elliott Place, Deputy, Marshall Islands, 3530\",
-    \"about\": \"Consectetur ad laboris occaecat anim nulla ipsum exercitation Lorem eiusmod dolore culpa. Mollit elit culpa nostrud labore velit excepteur eiusmod consectetur incididunt pariatur sint reprehenderit commodo incididunt. Labore elit reprehenderit labore ea labore ad magna laborum eiusmod non in voluptate esse. In excepteur elit adipisicing id laborum laboris qui velit eiusmod.\r\n\",
+    \"about\": \"Consectetur ad laboris occaecat anim nulla ipsum exercitation Lorem eiusmod dolore culpa. Mollit elit culpa nostrud labore velit excepteur eiusmod consectetur incididunt pariatur sint reprehenderit commodo incididunt. Labore elit reprehenderit labore ea labore ad magna laborum eiusmod non in voluptate esse. In excepteur elit adipisicing id laborum laboris qui velit eiusmod.\\r\\n\",
     \"registered\": \"2014-03-27T08:37:54 -00:00\",
     \"latitude\": -56.506466,
     \"longitude\": -157.849193,
@@ -454,7 +454,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"krystalthomas@elpro.com\",
     \"phone\": \"+1 (891) 561-3938\",
     \"address\": \"394 Ryerson Street, Thornport, Kansas, 2002\",
-    \"about\": \"Est amet nostrud culpa cupidatat. Ea amet sit sit ex ad ut consectetur ullamco nostrud enim amet reprehenderit. Ut sunt reprehenderit cillum pariatur. Ex reprehenderit exercitation laborum ex.\r\n\",
+    \"about\": \"Est amet nostrud culpa cupidatat. Ea amet sit sit ex ad ut consectetur ullamco nostrud enim amet reprehenderit. Ut sunt reprehenderit cillum pariatur. Ex reprehenderit exercitation laborum ex.\\r\\n\",
     \"registered\": \"2014-04-04T06:01:40 -01:00\",
     \"latitude\": 65.437772,
     \"longitude\": -68.878508,
@@ -499,7 +499,7 @@ const JSON_PAYLOAD : &str = "[
     \"email\": \"raymondvalencia@extrawear.com\",
     \"phone\": \"+1 (945) 543-3179\",
     \"address\": \"200 Seagate Terrace, Delco, Virgin Islands, 1955\",
-    \"about\": \"Voluptate nostrud occaecat aliquip labore. Aliqua voluptate irure nulla eiusmod velit duis commodo officia ullamco eiusmod sunt proident officia. Ex amet et incididunt reprehenderit voluptate ipsum eu id ullamco pariatur esse et aliquip commodo. Ullamco do aute esse qui sunt id eiusmod sunt. Anim dolor commodo aliquip nisi enim labore.\r\n\",
+    \"about\": \"Voluptate nostrud occaecat aliquip labore. Aliqua voluptate irure nulla eiusmod velit duis commodo officia ullamco eiusmod sunt proident officia. Ex amet et incididunt reprehenderit voluptate ipsum eu id ullamco pariatur esse et aliquip commodo. Ullamco do aute esse qui sunt id eiusmod sunt. Anim dolor commodo aliquip nisi enim labore.\\r\\n\",
     \"registered\": \"2019-10-11T05:11:05 -01:00\",
     \"latitude\": 16.392988,
     \"longitude\": 96.596231,